@@ -0,0 +1,126 @@
+use crate::results::Results;
+use crate::stats::format_nanos;
+
+use std::fmt;
+
+/// Changes below this many nanoseconds are noise, however large they are relative to the stage.
+const NOISE_FLOOR: u64 = 10_000;
+
+/// A stage whose median differs between the baseline and the current results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub day: u32,
+    pub stage: String,
+    pub baseline: u64,
+    pub current: u64,
+}
+
+impl Change {
+    /// Relative change of the median in percent, positive if the stage got slower.
+    pub fn percent(&self) -> f64 {
+        (self.current as f64 / self.baseline as f64 - 1.0) * 100.0
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day{} {}: {} -> {} ({:+.1}%)",
+            self.day,
+            self.stage,
+            format_nanos(self.baseline),
+            format_nanos(self.current),
+            self.percent()
+        )
+    }
+}
+
+/// Stages that are present in both results and got slower by more than `tolerance` percent.
+/// Stages missing from the baseline are new and cannot regress.
+pub fn regressions(baseline: &Results, current: &Results, tolerance: f64) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for (day, stages) in &current.days {
+        let Some(baseline_stages) = baseline.days.get(day) else {
+            continue;
+        };
+        for (stage, current_stage) in stages {
+            let Some(baseline_stage) = baseline_stages.get(stage) else {
+                continue;
+            };
+            let change = Change {
+                day: *day,
+                stage: stage.clone(),
+                baseline: baseline_stage.median,
+                current: current_stage.median,
+            };
+            let is_noise = change.current < change.baseline + NOISE_FLOOR;
+            if !is_noise && change.percent() > tolerance {
+                changes.push(change);
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(reports: &[(u32, &str)]) -> Results {
+        let mut results = Results::new(0, 1);
+        for (day, report) in reports {
+            results.add_report(*day, report);
+        }
+        results
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = results(&[
+            (4, r#"{"parse":[100000],"part1":[100000],"part2":[100000]}"#),
+            (5, r#"{"part1":[100000]}"#),
+        ]);
+        let current = results(&[
+            (4, r#"{"parse":[105000],"part1":[150000],"part2":[50000]}"#),
+            (5, r#"{"part1":[300000]}"#),
+            (6, r#"{"part1":[900000]}"#),
+        ]);
+
+        let changes = regressions(&baseline, &current, 10.0);
+        let expected = vec![
+            Change {
+                day: 4,
+                stage: "part1".to_string(),
+                baseline: 100_000,
+                current: 150_000,
+            },
+            Change {
+                day: 5,
+                stage: "part1".to_string(),
+                baseline: 100_000,
+                current: 300_000,
+            },
+        ];
+        assert_eq!(changes, expected);
+        assert_eq!(
+            changes[0].to_string(),
+            "day4 part1: 100.0 µs -> 150.0 µs (+50.0%)"
+        );
+        assert!(regressions(&baseline, &current, 200.0).is_empty());
+    }
+
+    #[test]
+    fn test_noise_is_no_regression() {
+        let baseline = results(&[(18, r#"{"part1":[100]}"#)]);
+        let current = results(&[(18, r#"{"part1":[5000]}"#)]);
+        assert!(regressions(&baseline, &current, 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_new_stage_is_no_regression() {
+        let baseline = results(&[(16, r#"{"part1":[100000]}"#)]);
+        let current = results(&[(16, r#"{"parse":[900000],"part1":[100000]}"#)]);
+        assert!(regressions(&baseline, &current, 10.0).is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+mod compare;
+mod results;
+mod stats;
+
+use results::Results;
+
+use std::path::PathBuf;
+use std::process::Command;
+
+const NUM_DAYS: u32 = 25;
+
+/// Stages may get this many percent slower than in the baseline before they count as regressed.
+const DEFAULT_TOLERANCE: f64 = 10.0;
+
+fn bench_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn results_path() -> PathBuf {
+    bench_dir().join("results.json")
+}
+
+fn baseline_path() -> PathBuf {
+    bench_dir().join("baseline.json")
+}
+
+/// Comma separated days like `4,5,6`.
+fn parse_days(arg: &str) -> Vec<u32> {
+    arg.split(',')
+        .map(|day| {
+            let day = day.trim().trim_start_matches("day").parse().unwrap();
+            assert!((1..=NUM_DAYS).contains(&day), "there is no day {day}");
+            day
+        })
+        .collect()
+}
+
+/// Runs `dayN timings` in release mode and returns the line it reports, or why the day can't be
+/// benched.
+fn run_day(day: u32, warmup: usize, num_samples: usize) -> Result<String, &'static str> {
+    let dir = bench_dir().join("..").join(format!("day{day}"));
+    if !dir.join("input").exists() {
+        return Err("it has no input");
+    }
+    // unsolved days don't depend on the timings crate, so they have no `timings` command
+    let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+    if !manifest.contains("timings") {
+        return Err("it has no timings");
+    }
+
+    let output = Command::new("cargo")
+        .args(["run", "--release", "-q", "--", "timings"])
+        .arg(warmup.to_string())
+        .arg(num_samples.to_string())
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "day{day} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    Ok(stdout.lines().last().unwrap().to_string())
+}
+
+/// Prints every regression against the baseline, and whether there were any.
+fn compare(current: &Results, tolerance: f64) -> bool {
+    let baseline = Results::load(&baseline_path());
+    let regressions = compare::regressions(&baseline, current, tolerance);
+    if regressions.is_empty() {
+        println!("no stage got more than {tolerance}% slower than the baseline");
+        return false;
+    }
+    println!("regressions of more than {tolerance}% against the baseline:");
+    for regression in &regressions {
+        println!("  {regression}");
+    }
+    true
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `bench baseline` keeps the last results as the baseline to compare against
+    if args.first().map(String::as_str) == Some("baseline") {
+        std::fs::copy(results_path(), baseline_path()).unwrap();
+        println!("saved {} as baseline", results_path().display());
+        return;
+    }
+
+    // `bench compare [tolerance in %]` compares the last results against the baseline
+    if args.first().map(String::as_str) == Some("compare") {
+        let tolerance = args
+            .get(1)
+            .map_or(DEFAULT_TOLERANCE, |tolerance| tolerance.parse().unwrap());
+        if compare(&Results::load(&results_path()), tolerance) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // `bench [warmup] [samples] [days]` benches the given days, or every solved day with an input
+    let warmup = args.first().map_or(1, |warmup| warmup.parse().unwrap());
+    let num_samples = args.get(1).map_or(5, |samples| samples.parse().unwrap());
+    let days = args
+        .get(2)
+        .map_or_else(|| (1..=NUM_DAYS).collect(), |days| parse_days(days));
+
+    let mut results = Results::new(warmup, num_samples);
+    for day in days {
+        eprintln!("benching day{day}");
+        match run_day(day, warmup, num_samples) {
+            Ok(report) => results.add_report(day, &report),
+            Err(reason) => eprintln!("skipping day{day}, {reason}"),
+        }
+    }
+
+    print!("{}", results.table());
+    results.save(&results_path());
+
+    if baseline_path().exists() && compare(&results, DEFAULT_TOLERANCE) {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("4"), vec![4]);
+        assert_eq!(parse_days("4,day5, 25"), vec![4, 5, 25]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_parse_unknown_day() {
+        parse_days("26");
+    }
+}
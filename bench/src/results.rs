@@ -0,0 +1,145 @@
+use crate::stats::{format_nanos, Stage};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+pub const STAGES: [&str; 3] = ["parse", "part1", "part2"];
+
+/// Suffix of a part which parses the input itself, as its day has no separate parser.
+const INCL_PARSE: &str = " (incl. parse)";
+
+/// Every stage of every benched day, keyed by day and stage name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Results {
+    pub warmup: usize,
+    pub num_samples: usize,
+    pub days: BTreeMap<u32, BTreeMap<String, Stage>>,
+}
+
+impl Results {
+    pub fn new(warmup: usize, num_samples: usize) -> Self {
+        Self {
+            warmup,
+            num_samples,
+            days: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the stages of the line a day prints for `dayN timings`, like `{"part1":[5,7]}`.
+    pub fn add_report(&mut self, day: u32, report: &str) {
+        let report: BTreeMap<String, Vec<u64>> = serde_json::from_str(report.trim())
+            .unwrap_or_else(|err| panic!("day{day} reported '{report}': {err}"));
+        let stages = report
+            .into_iter()
+            .map(|(stage, samples)| (stage, Stage::new(samples)))
+            .collect();
+        self.days.insert(day, stages);
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let json = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("cannot read {}: {err}", path.display()));
+        serde_json::from_str(&json).unwrap()
+    }
+
+    pub fn save(&self, path: &Path) {
+        let json = serde_json::to_string_pretty(self).unwrap();
+        std::fs::write(path, json)
+            .unwrap_or_else(|err| panic!("cannot write {}: {err}", path.display()));
+    }
+
+    /// The median of every stage, one day per row. Days without a parse stage show a `-`, and
+    /// their parts are marked with a `*` as they include parsing.
+    pub fn table(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "median of {} samples after {} warmup runs",
+            self.num_samples, self.warmup
+        )
+        .unwrap();
+        write!(out, "{:>5}", "day").unwrap();
+        for stage in STAGES {
+            write!(out, " {stage:>10}").unwrap();
+        }
+        writeln!(out, " {:>10}", "total").unwrap();
+
+        for (day, stages) in &self.days {
+            write!(out, "{day:>5}").unwrap();
+            for stage in STAGES {
+                let cell = match (
+                    stages.get(stage),
+                    stages.get(&format!("{stage}{INCL_PARSE}")),
+                ) {
+                    (Some(stage), _) => format_nanos(stage.median),
+                    (None, Some(stage)) => format!("{}*", format_nanos(stage.median)),
+                    (None, None) => "-".to_string(),
+                };
+                write!(out, " {cell:>10}").unwrap();
+            }
+            let total = stages.values().map(|stage| stage.median).sum();
+            writeln!(out, " {:>10}", format_nanos(total)).unwrap();
+        }
+
+        let includes_parse = |stages: &BTreeMap<String, Stage>| {
+            stages.keys().any(|stage| stage.ends_with(INCL_PARSE))
+        };
+        if self.days.values().any(includes_parse) {
+            writeln!(out, "* includes parsing, as the day has no separate parser").unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Results {
+        let mut results = Results::new(3, 2);
+        results.add_report(
+            1,
+            r#"{"part1 (incl. parse)":[2000,4000],"part2 (incl. parse)":[500,500]}"#,
+        );
+        results.add_report(16, "{\"parse\":[10,20],\"part1\":[5,5],\"part2\":[7,7]}\n");
+        results
+    }
+
+    #[test]
+    fn test_add_report() {
+        let results = example();
+        assert_eq!(results.days.len(), 2);
+        assert!(!results.days[&1].contains_key("parse"));
+        assert_eq!(
+            results.days[&1]["part1 (incl. parse)"],
+            Stage::new(vec![2000, 4000])
+        );
+        assert_eq!(results.days[&16]["parse"].median, 15);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_broken_report() {
+        Results::new(0, 1).add_report(1, "Result1 is 5");
+    }
+
+    #[test]
+    fn test_table() {
+        let expected = "\
+            median of 2 samples after 3 warmup runs\n  \
+              day      parse      part1      part2      total\n    \
+                1          -    3.0 µs*    500 ns*     3.5 µs\n   \
+               16      15 ns       5 ns       7 ns      27 ns\n\
+            * includes parsing, as the day has no separate parser\n";
+        assert_eq!(example().table(), expected);
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let results = example();
+        let json = serde_json::to_string(&results).unwrap();
+        assert_eq!(serde_json::from_str::<Results>(&json).unwrap(), results);
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+/// Samples of a single stage in nanoseconds, together with their summary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stage {
+    pub samples: Vec<u64>,
+    pub min: u64,
+    pub median: u64,
+    pub mean: u64,
+}
+
+impl Stage {
+    pub fn new(samples: Vec<u64>) -> Self {
+        assert!(!samples.is_empty(), "a stage needs at least one sample");
+
+        let mut sorted = samples.clone();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+        let mean = sorted.iter().sum::<u64>() / sorted.len() as u64;
+
+        Self {
+            min: sorted[0],
+            median,
+            mean,
+            samples,
+        }
+    }
+}
+
+/// Nanoseconds in the largest unit that keeps at least one digit before the point.
+pub fn format_nanos(nanos: u64) -> String {
+    let nanos = nanos as f64;
+    if nanos < 1e3 {
+        format!("{nanos} ns")
+    } else if nanos < 1e6 {
+        format!("{:.1} µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.1} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_odd() {
+        let stage = Stage::new(vec![9, 1, 5]);
+        assert_eq!(stage.min, 1);
+        assert_eq!(stage.median, 5);
+        assert_eq!(stage.mean, 5);
+        assert_eq!(stage.samples, vec![9, 1, 5]);
+    }
+
+    #[test]
+    fn test_stage_even() {
+        let stage = Stage::new(vec![10, 2, 4, 100]);
+        assert_eq!(stage.min, 2);
+        assert_eq!(stage.median, 7);
+        assert_eq!(stage.mean, 29);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stage_without_samples() {
+        Stage::new(Vec::new());
+    }
+
+    #[test]
+    fn test_format_nanos() {
+        assert_eq!(format_nanos(999), "999 ns");
+        assert_eq!(format_nanos(1_250), "1.2 µs");
+        assert_eq!(format_nanos(32_606_992), "32.6 ms");
+        assert_eq!(format_nanos(378_493_120_331), "378.49 s");
+    }
+}
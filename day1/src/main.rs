@@ -1,3 +1,5 @@
+use timings::Stage;
+
 fn part1_get_first_digit(line: &str, from_right: bool) -> Option<u32> {
     let mut num_iter = line.chars().filter(|c| c.is_digit(10));
     let num = if from_right {
@@ -77,6 +79,13 @@ fn part2(input: &str) -> u32 {
 
 fn main() {
     let input = read_input();
+
+    // `day1 timings [warmup] [samples]` times both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("part1 (incl. parse)", || part1(&input)),
+        Stage::new("part2 (incl. parse)", || part2(&input)),
+    ]);
+
     let result1 = part1(&input);
     let result2 = part2(&input);
     println!("Part1 result is {result1}");
@@ -1,5 +1,8 @@
 mod part1;
 mod part2;
+mod render;
+
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -10,10 +13,31 @@ pub fn read_input() -> String {
 
 fn main() {
     let input = read_input();
+
+    // `day10 render` shows the flooded maze, `day10 render <file.ppm>` writes it as image
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("render") {
+        let maze = part2::flooded_maze(&input);
+        match args.get(1) {
+            Some(path) => std::fs::write(path, render::ppm(&maze)).unwrap(),
+            None => print!("{}", render::ansi(&maze)),
+        }
+        return;
+    }
+
+    // `day10 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    let parse = || part1::Maze::new(&input).unwrap();
+    timings::run_from_args(&mut [
+        Stage::new("parse", parse),
+        Stage::with_setup("part1", parse, |maze| part1::steps_to_farthest(&maze)),
+        Stage::with_setup("part2", parse, part2::count_inner),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
+    let result2_shoelace = part2::run_shoelace(&input);
     println!("Result1 is {result1}");
-    println!("Result2 is {result2}");
+    println!("Result2 is {result2} (shoelace: {result2_shoelace})");
 }
 
 #[cfg(test)]
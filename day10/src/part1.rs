@@ -1,14 +1,19 @@
 use std::iter;
 
 pub fn run(input: &str) -> usize {
-    let maze = Maze::new(input, '-');
-    let mut hamster_0 = Hamster::new(maze.start(), Direction::Right);
-    let mut hamster_1 = Hamster::new(maze.start(), Direction::Left);
+    let maze = Maze::new(input).unwrap_or_else(|error| panic!("invalid maze: {error}"));
+    steps_to_farthest(&maze)
+}
+
+pub fn steps_to_farthest(maze: &Maze) -> usize {
+    let [direction_0, direction_1] = maze.start_openings();
+    let mut hamster_0 = Hamster::new(maze.start(), direction_0);
+    let mut hamster_1 = Hamster::new(maze.start(), direction_1);
     let mut num_steps = 0;
 
     'hamsters_walking: loop {
-        hamster_0.walk_maze(&maze);
-        hamster_1.walk_maze(&maze);
+        hamster_0.walk_maze(maze);
+        hamster_1.walk_maze(maze);
         num_steps += 1;
 
         if hamster_0.position == hamster_1.position() {
@@ -84,6 +89,14 @@ impl Coords {
         Coords { x, y }
     }
 
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
     pub fn move_into_direction(&self, direction: Direction) -> Option<Self> {
         let mut ret = *self;
         match direction {
@@ -108,6 +121,41 @@ impl Coords {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum MazeError {
+    NoStart,
+    /// the pipe at `coords` opens towards `direction`, but nothing connects back there
+    DanglingPipe {
+        coords: Coords,
+        direction: Direction,
+    },
+    /// the start field closes a loop as each of these pipes
+    AmbiguousStart(Vec<char>),
+    NoLoop,
+}
+
+impl std::fmt::Display for MazeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoStart => write!(f, "there is no starting field 'S'"),
+            // thanks to the border, coords are the 1-based line and column of the input
+            Self::DanglingPipe { coords, direction } => write!(
+                f,
+                "dangling pipe at line {}, column {}: nothing connects to its {direction:?} side",
+                coords.y, coords.x
+            ),
+            Self::AmbiguousStart(candidates) => write!(
+                f,
+                "the starting field could be any of {}",
+                candidates.iter().collect::<String>()
+            ),
+            Self::NoLoop => write!(f, "the starting field is not part of a loop"),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
+
 /// Coords are `[y][x]`
 pub struct Maze {
     fields: Vec<Vec<Field>>,
@@ -115,8 +163,15 @@ pub struct Maze {
 }
 
 impl Maze {
-    /// starting position will be overwritten after parsing with `start_char`
-    pub fn new(input: &str, start_char: char) -> Self {
+    /// Parses the maze and replaces the starting field with the only pipe that closes a loop.
+    pub fn new(input: &str) -> Result<Self, MazeError> {
+        let mut maze = Self::parse(input).ok_or(MazeError::NoStart)?;
+        maze.infer_start_pipe()?;
+        Ok(maze)
+    }
+
+    /// Parses the maze without any checks, the starting field does not contain a pipe.
+    pub fn parse(input: &str) -> Option<Self> {
         fn parse_line(line: &str) -> Vec<Field> {
             let prepend = iter::once(Field::default());
             let append = prepend.clone();
@@ -129,14 +184,89 @@ impl Maze {
         let append = prepend.clone();
 
         let fields_iter = input.lines().map(parse_line);
-        let mut fields: Vec<Vec<_>> = prepend.chain(fields_iter).chain(append).collect();
+        let fields: Vec<Vec<_>> = prepend.chain(fields_iter).chain(append).collect();
 
-        let start = Self::find_start_coords(&fields).unwrap();
+        let start = Self::find_start_coords(&fields)?;
+        Some(Self { fields, start })
+    }
 
-        // overwrite starting field
-        fields[start.y][start.x] = Field::from(start_char);
+    fn infer_start_pipe(&mut self) -> Result<(), MazeError> {
+        let start = self.start;
+        let connects = |direction: Direction| {
+            start
+                .move_into_direction(direction)
+                .and_then(|coords| self.get_field(coords))
+                .and_then(Field::pipe)
+                .is_some_and(|pipe| pipe.openings().contains(&direction.opposite()))
+        };
+        let candidates: Vec<char> = PIPE_CHARS
+            .into_iter()
+            .filter(|pipe_char| {
+                Pipe::from(*pipe_char)
+                    .openings()
+                    .iter()
+                    .all(|d| connects(*d))
+            })
+            .collect();
+
+        let mut loops = Vec::new();
+        let mut last_error = MazeError::NoLoop;
+        for candidate in &candidates {
+            self.fields[start.y][start.x].pipe = Some(Pipe::from(*candidate));
+            match self.validate_loop() {
+                Ok(()) => loops.push(*candidate),
+                Err(error) => last_error = error,
+            }
+        }
 
-        Self { fields, start }
+        match loops[..] {
+            [start_char] => {
+                self.fields[start.y][start.x].pipe = Some(Pipe::from(start_char));
+                Ok(())
+            }
+            [] => {
+                self.fields[start.y][start.x].pipe = None;
+                // with a single candidate, its dead end is more helpful than a missing loop
+                match candidates.len() {
+                    1 => Err(last_error),
+                    _ => Err(MazeError::NoLoop),
+                }
+            }
+            _ => {
+                self.fields[start.y][start.x].pipe = None;
+                Err(MazeError::AmbiguousStart(loops))
+            }
+        }
+    }
+
+    /// Walks along the pipes from the start until it is reached again.
+    pub fn validate_loop(&self) -> Result<(), MazeError> {
+        let start_pipe = self
+            .get_field(self.start)
+            .and_then(Field::pipe)
+            .ok_or(MazeError::NoLoop)?;
+        let mut position = self.start;
+        let mut direction = start_pipe.openings()[0];
+
+        loop {
+            let dangling = MazeError::DanglingPipe {
+                coords: position,
+                direction,
+            };
+            let next_position = position
+                .move_into_direction(direction)
+                .ok_or(dangling.clone())?;
+            let next_pipe = self
+                .get_field(next_position)
+                .and_then(Field::pipe)
+                .ok_or(dangling.clone())?;
+            direction = next_pipe.exit(direction).ok_or(dangling)?;
+            position = next_position;
+
+            if position == self.start {
+                return Ok(());
+            }
+        }
     }
 
     pub fn find_start_coords(fields: &[Vec<Field>]) -> Option<Coords> {
@@ -153,6 +283,16 @@ impl Maze {
         self.start
     }
 
+    /// the two directions in which the loop leaves the start
+    pub fn start_openings(&self) -> [Direction; 2] {
+        *self
+            .get_field(self.start)
+            .unwrap()
+            .pipe()
+            .unwrap()
+            .openings()
+    }
+
     pub fn get_field(&self, coords: Coords) -> Option<&Field> {
         self.fields.get(coords.y)?.get(coords.x)
     }
@@ -161,6 +301,14 @@ impl Maze {
         self.fields.get_mut(coords.y)?.get_mut(coords.x)
     }
 
+    /// all lines of the maze, without the empty border added while parsing
+    pub fn lines(&self) -> impl Iterator<Item = &[Field]> {
+        let height = self.fields.len();
+        self.fields[1..height - 1]
+            .iter()
+            .map(|line| &line[1..line.len() - 1])
+    }
+
     pub fn size(&self) -> usize {
         self.fields.len() * self.fields[0].len()
     }
@@ -222,6 +370,8 @@ impl Field {
     }
 }
 
+const PIPE_CHARS: [char; 6] = ['|', '-', 'L', 'J', '7', 'F'];
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Pipe {
     openings: [Direction; 2],
@@ -247,10 +397,22 @@ impl From<char> for Pipe {
 }
 
 impl Pipe {
-    fn openings(&self) -> &[Direction; 2] {
+    pub fn openings(&self) -> &[Direction; 2] {
         &self.openings
     }
 
+    /// Like [`Pipe::follow`], but checks that the pipe actually opens to the entering side.
+    pub fn exit(&self, entering_direction: Direction) -> Option<Direction> {
+        let [first, second] = self.openings;
+        if first.is_opposite(entering_direction) {
+            Some(second)
+        } else if second.is_opposite(entering_direction) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+
     pub fn fill(&mut self) {
         self.full = true;
     }
@@ -320,7 +482,7 @@ mod tests {
         ....\n\
         ..S.\n\
         ....";
-        let maze = Maze::new(input, '.');
+        let maze = Maze::parse(input).unwrap();
         let coords = maze.start();
         assert_eq!(coords.x, 3);
         assert_eq!(coords.y, 2);
@@ -332,7 +494,7 @@ mod tests {
             .S-7.\n\
             .|.|.\n\
             .L-J.";
-        let maze = Maze::new(input, 'F');
+        let maze = Maze::new(input).unwrap();
         assert_eq!(
             maze.fields
                 .iter()
@@ -351,7 +513,11 @@ mod tests {
         L|7||\n\
         -L-J|\n\
         L|-JF";
-        let maze = Maze::new(input, 'F');
+        let maze = Maze::new(input).unwrap();
+        assert_eq!(
+            maze.get_field(maze.start()).unwrap().pipe(),
+            Some(&Pipe::from('F'))
+        );
         assert_eq!(maze.start().x, 2);
         assert_eq!(maze.start().y, 2);
 
@@ -372,4 +538,60 @@ mod tests {
         hamster.walk_maze(&maze);
         assert_eq!(hamster.position(), maze.start());
     }
+
+    #[test]
+    fn test_vertical_start() {
+        let input = "\
+            F-7\n\
+            S.|\n\
+            L-J";
+        assert_eq!(run(input), 4);
+    }
+
+    #[test]
+    fn test_no_start() {
+        let input = "\
+            F-7\n\
+            L-J";
+        assert_eq!(Maze::new(input).err(), Some(MazeError::NoStart));
+    }
+
+    #[test]
+    fn test_dangling_pipe() {
+        let input = "\
+            .....\n\
+            .S-7.\n\
+            .|...\n\
+            .L-J.\n\
+            .....";
+        let expected = MazeError::DanglingPipe {
+            coords: Coords::new(4, 2),
+            direction: Direction::Down,
+        };
+        assert_eq!(Maze::new(input).err(), Some(expected.clone()));
+        assert_eq!(
+            expected.to_string(),
+            "dangling pipe at line 2, column 4: nothing connects to its Down side"
+        );
+    }
+
+    #[test]
+    fn test_no_loop() {
+        let input = "\
+            .S-.\n\
+            ....";
+        assert_eq!(Maze::new(input).err(), Some(MazeError::NoLoop));
+    }
+
+    #[test]
+    fn test_ambiguous_start() {
+        let input = "\
+            ..F-7\n\
+            ..|.|\n\
+            F-S-J\n\
+            |.|..\n\
+            L-J..";
+        let expected = MazeError::AmbiguousStart(vec!['L', '7']);
+        assert_eq!(Maze::new(input).err(), Some(expected));
+    }
 }
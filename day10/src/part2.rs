@@ -1,19 +1,34 @@
 use crate::part1::{Coords, Direction, Hamster, Maze};
 
 pub fn run(input: &str) -> usize {
-    run_private(input, '-')
+    let maze = Maze::new(input).unwrap_or_else(|error| panic!("invalid maze: {error}"));
+    count_inner(maze)
 }
 
-fn run_private(input: &str, start_char: char) -> usize {
-    let mut maze = Maze::new(input, start_char);
-    pooping_hamster(&mut maze);
-    flood_outer(&mut maze);
-    let (flood_start, flood_direction, inner_side) = find_inner_direction_hamster(&mut maze);
-    flooding_hamster(&mut maze, flood_start, flood_direction, inner_side);
-
+pub fn count_inner(mut maze: Maze) -> usize {
+    flood_loop(&mut maze);
     maze.into_iter().filter(|field| field.is_inner()).count()
 }
 
+/// The maze with its loop filled and all other fields marked as inner or outer.
+pub fn flooded_maze(input: &str) -> Maze {
+    let mut maze = Maze::new(input).unwrap_or_else(|error| panic!("invalid maze: {error}"));
+    flood_loop(&mut maze);
+    maze
+}
+
+fn flood_loop(maze: &mut Maze) {
+    pooping_hamster(maze);
+    flood_outer(maze);
+    let (flood_start, flood_direction, inner_side) = find_inner_direction_hamster(maze);
+    flooding_hamster(maze, flood_start, flood_direction, inner_side);
+}
+
+pub fn run_shoelace(input: &str) -> usize {
+    let maze = Maze::new(input).unwrap_or_else(|error| panic!("invalid maze: {error}"));
+    shoelace_hamster(&maze)
+}
+
 enum Side {
     Left,
     Right,
@@ -97,6 +112,35 @@ fn flooding_hamster(maze: &mut Maze, start: Coords, start_direction: Direction,
     }
 }
 
+/// Counts the enclosed fields without marking anything in the maze.
+///
+/// The shoelace formula gives the area `A` of the polygon through the centers of all loop
+/// fields. As those centers are exactly the `b` lattice points on its boundary, Pick's theorem
+/// `A = i + b / 2 - 1` then yields the number of inner fields `i`.
+fn shoelace_hamster(maze: &Maze) -> usize {
+    let mut hamster = Hamster::new(maze.start(), Direction::Left);
+    let mut vertices = Vec::new();
+
+    loop {
+        vertices.push(hamster.position());
+        hamster.walk_maze(maze);
+        if hamster.position() == maze.start() {
+            break;
+        }
+    }
+
+    let next_vertices = vertices.iter().cycle().skip(1);
+    let double_area = vertices
+        .iter()
+        .zip(next_vertices)
+        .map(|(a, b)| (a.x() * b.y()) as isize - (b.x() * a.y()) as isize)
+        .sum::<isize>()
+        .unsigned_abs();
+    let boundary = vertices.len();
+
+    (double_area + 2 - boundary) / 2
+}
+
 fn flood_outer(maze: &mut Maze) {
     let start = Coords::new(0, 0);
     flood(maze, start, false);
@@ -156,12 +200,29 @@ fn flood(maze: &mut Maze, start: Coords, inner: bool) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_shoelace_single_field() {
+        let input = "\
+            .....\n\
+            .S-7.\n\
+            .|.|.\n\
+            .L-J.\n\
+            .....";
+        assert_eq!(run_shoelace(input), 1);
+    }
+
+    #[test]
+    fn test_shoelace_matches_flood() {
+        let input = crate::read_input();
+        assert_eq!(run_shoelace(&input), run(&input));
+    }
+
     #[test]
     fn test_flood_outer_all() {
         let input = "\
             S|.\n\
             .|.";
-        let mut maze = Maze::new(input, '.');
+        let mut maze = Maze::parse(input).unwrap();
         flood_outer(&mut maze);
         maze.into_iter().for_each(|field| assert!(field.is_outer()));
     }
@@ -172,7 +233,7 @@ mod tests {
             FS7.\n\
             |.|.\n\
             L-J.";
-        let mut maze = Maze::new(input, '-');
+        let mut maze = Maze::new(input).unwrap();
 
         fn fill_field(maze: &mut Maze, x: usize, y: usize) {
             maze.get_field_mut(Coords::new(x, y))
@@ -205,6 +266,7 @@ mod tests {
             .|..|.\n\
             .L--J.";
         assert_eq!(run(input), 3);
+        assert_eq!(run_shoelace(input), 3);
     }
 
     #[test]
@@ -219,7 +281,8 @@ mod tests {
             .|..||..|.\n\
             .L--JL--J.\n\
             ..........";
-        assert_eq!(run_private(input, 'F'), 4);
+        assert_eq!(run(input), 4);
+        assert_eq!(run_shoelace(input), 4);
     }
 
     #[test]
@@ -235,7 +298,8 @@ mod tests {
             .....|FJLJ|FJ|F7|.LJ\n\
             ....FJL-7.||.||||...\n\
             ....L---J.LJ.LJLJ...";
-        assert_eq!(run_private(input, 'F'), 8);
+        assert_eq!(run(input), 8);
+        assert_eq!(run_shoelace(input), 8);
     }
 
     #[test]
@@ -251,6 +315,7 @@ mod tests {
             7-L-JL7||F7|L7F-7F7|\n\
             L.L7LFJ|||||FJL7||LJ\n\
             L7JLJL-JLJLJL--JLJ.L";
-        assert_eq!(run_private(input, '7'), 10);
+        assert_eq!(run(input), 10);
+        assert_eq!(run_shoelace(input), 10);
     }
 }
@@ -0,0 +1,185 @@
+use crate::part1::{Direction, Field, Maze, Pipe};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const INNER_BACKGROUND: &str = "\x1b[42m";
+const OUTER_BACKGROUND: &str = "\x1b[44m";
+const UNMARKED_BACKGROUND: &str = "\x1b[41m";
+
+/// pixels per field side in images, enough to draw a pipe through the center
+const TILE_SIZE: usize = 3;
+const LOOP_COLOR: [u8; 3] = [255, 255, 255];
+const JUNK_COLOR: [u8; 3] = [128, 128, 128];
+const LOOP_BACKGROUND_COLOR: [u8; 3] = [0, 0, 0];
+const INNER_COLOR: [u8; 3] = [0, 160, 0];
+const OUTER_COLOR: [u8; 3] = [0, 0, 160];
+const UNMARKED_COLOR: [u8; 3] = [200, 0, 0];
+
+pub fn box_char(pipe: &Pipe) -> char {
+    let opens = |direction| pipe.openings().contains(&direction);
+    match (
+        opens(Direction::Up),
+        opens(Direction::Down),
+        opens(Direction::Left),
+        opens(Direction::Right),
+    ) {
+        (true, true, _, _) => '│',
+        (_, _, true, true) => '─',
+        (true, _, _, true) => '└',
+        (true, _, true, _) => '┘',
+        (_, true, true, _) => '┐',
+        (_, true, _, true) => '┌',
+        _ => unreachable!("pipes always have two openings"),
+    }
+}
+
+fn is_loop(field: &Field) -> bool {
+    field.pipe().is_some_and(Pipe::is_full)
+}
+
+/// Box drawing characters with the loop in bold, all other pipes dimmed, and inner and outer
+/// fields colored. Fields that neither flood reached stay red, as does everything in a maze
+/// that was never flooded.
+pub fn ansi(maze: &Maze) -> String {
+    let mut out = String::new();
+    for line in maze.lines() {
+        for field in line {
+            if is_loop(field) {
+                let symbol = box_char(field.pipe().unwrap());
+                out.push_str(&format!("{BOLD}{symbol}{RESET}"));
+                continue;
+            }
+
+            let background = if field.is_inner() {
+                INNER_BACKGROUND
+            } else if field.is_outer() {
+                OUTER_BACKGROUND
+            } else {
+                UNMARKED_BACKGROUND
+            };
+            let symbol = field.pipe().map_or(' ', box_char);
+            out.push_str(&format!("{background}{DIM}{symbol}{RESET}"));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Binary PPM image with the same colors as [`ansi`].
+pub fn ppm(maze: &Maze) -> Vec<u8> {
+    let lines: Vec<&[Field]> = maze.lines().collect();
+    let width = lines.first().map_or(0, |line| line.len()) * TILE_SIZE;
+    let height = lines.len() * TILE_SIZE;
+
+    let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+    for line in &lines {
+        for row in 0..TILE_SIZE {
+            for field in line.iter() {
+                for column in 0..TILE_SIZE {
+                    image.extend(pixel(field, column, row));
+                }
+            }
+        }
+    }
+    image
+}
+
+fn pixel(field: &Field, column: usize, row: usize) -> [u8; 3] {
+    let background = if is_loop(field) {
+        LOOP_BACKGROUND_COLOR
+    } else if field.is_inner() {
+        INNER_COLOR
+    } else if field.is_outer() {
+        OUTER_COLOR
+    } else {
+        UNMARKED_COLOR
+    };
+
+    let Some(pipe) = field.pipe() else {
+        return background;
+    };
+    let opens = |direction| pipe.openings().contains(&direction);
+    let center = TILE_SIZE / 2;
+    let on_pipe = match (column, row) {
+        (column, row) if column == center && row == center => true,
+        (column, 0) if column == center => opens(Direction::Up),
+        (column, row) if column == center && row == TILE_SIZE - 1 => opens(Direction::Down),
+        (0, row) if row == center => opens(Direction::Left),
+        (column, row) if column == TILE_SIZE - 1 && row == center => opens(Direction::Right),
+        _ => false,
+    };
+
+    match (on_pipe, pipe.is_full()) {
+        (false, _) => background,
+        (true, true) => LOOP_COLOR,
+        (true, false) => JUNK_COLOR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part2::flooded_maze;
+
+    const INPUT: &str = "\
+        .....\n\
+        .S-7.\n\
+        .|.|.\n\
+        .L-J.\n\
+        ....|";
+
+    #[test]
+    fn test_box_chars() {
+        let symbols: String = "|-LJ7F"
+            .chars()
+            .map(|pipe_char| box_char(&Pipe::from(pipe_char)))
+            .collect();
+        assert_eq!(symbols, "│─└┘┐┌");
+    }
+
+    #[test]
+    fn test_ansi() {
+        let maze = flooded_maze(INPUT);
+        let ansi = ansi(&maze);
+        let lines: Vec<&str> = ansi.lines().collect();
+        assert_eq!(lines.len(), 5);
+
+        let outer = format!("{OUTER_BACKGROUND}{DIM} {RESET}");
+        let inner = format!("{INNER_BACKGROUND}{DIM} {RESET}");
+        let junk = format!("{OUTER_BACKGROUND}{DIM}│{RESET}");
+        let pipe = |symbol| format!("{BOLD}{symbol}{RESET}");
+        assert_eq!(lines[0], outer.repeat(5));
+        assert_eq!(
+            lines[2],
+            [outer.clone(), pipe('│'), inner, pipe('│'), outer.clone()].concat()
+        );
+        assert_eq!(lines[4], [outer.repeat(4), junk].concat());
+    }
+
+    #[test]
+    fn test_unflooded_maze_is_unmarked() {
+        let maze = Maze::new(INPUT).unwrap();
+        let ansi = ansi(&maze);
+        assert!(!ansi.contains(BOLD));
+        assert_eq!(ansi.matches(UNMARKED_BACKGROUND).count(), 25);
+    }
+
+    #[test]
+    fn test_ppm() {
+        let maze = flooded_maze(INPUT);
+        let image = ppm(&maze);
+        let header = "P6\n15 15\n255\n";
+        assert!(image.starts_with(header.as_bytes()));
+        assert_eq!(image.len(), header.len() + 15 * 15 * 3);
+
+        // center of the start field, which is a loop corner
+        let pixel_index = |x: usize, y: usize| header.len() + (y * 15 + x) * 3;
+        let center = pixel_index(4, 4);
+        assert_eq!(image[center..center + 3], LOOP_COLOR);
+        let corner = pixel_index(3, 3);
+        assert_eq!(image[corner..corner + 3], LOOP_BACKGROUND_COLOR);
+        let inner = pixel_index(7, 7);
+        assert_eq!(image[inner..inner + 3], INNER_COLOR);
+    }
+}
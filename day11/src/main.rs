@@ -1,6 +1,8 @@
 mod part1;
 mod part2;
 
+use timings::Stage;
+
 pub fn read_input() -> String {
     use std::fs;
 
@@ -9,7 +11,44 @@ pub fn read_input() -> String {
 }
 
 fn main() {
+    // `day11 layers <file> [rate]` sums up the distances in a universe of stacked 2D slices
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("layers") {
+        let input = std::fs::read_to_string(&args[1]).unwrap();
+        let rate = args.get(2).map_or(2, |rate| rate.parse().unwrap());
+        let mut universe = part1::Universe::from_layers(&input).unwrap();
+        universe.expand(rate);
+        println!("Result is {}", part1::sum_distances(&universe));
+        return;
+    }
+
     let input = read_input();
+
+    // `day11 distance <a> <b> [rate]` prints the distance between two galaxies, which are
+    // numbered from 1 like in the puzzle
+    if args.first().map(String::as_str) == Some("distance") {
+        let a: usize = args[1].parse().unwrap();
+        let b: usize = args[2].parse().unwrap();
+        let rate = args.get(3).map_or(2, |rate| rate.parse().unwrap());
+        let mut universe: part1::Universe<2> = input.parse().unwrap();
+        universe.expand(rate);
+        let galaxies = universe.galaxies();
+        println!("{}", galaxies[a - 1].distance(&galaxies[b - 1]));
+        return;
+    }
+
+    // `day11 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    let parse = || input.parse::<part1::Universe<2>>().unwrap();
+    timings::run_from_args(&mut [
+        Stage::new("parse", parse),
+        Stage::with_setup("part1", parse, |universe| {
+            part1::sum_expanded_distances(universe, 2)
+        }),
+        Stage::with_setup("part2", parse, |universe| {
+            part1::sum_expanded_distances(universe, 1_000_000)
+        }),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
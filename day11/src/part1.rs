@@ -1,126 +1,170 @@
 use std::str::FromStr;
 
-pub fn run(input: &str) -> usize {
-    let mut universe = Universe::from_str(input).unwrap();
-    universe.expand(2);
+pub fn run(input: &str) -> u128 {
+    sum_expanded_distances(Universe::from_str(input).unwrap(), 2)
+}
+
+pub fn sum_expanded_distances<const D: usize>(
+    mut universe: Universe<D>,
+    expansion_rate: u128,
+) -> u128 {
+    universe.expand(expansion_rate);
     sum_distances(&universe)
 }
 
-pub fn sum_distances(universe: &Universe) -> usize {
-    let galaxies = universe.galaxies();
-    galaxies
-        .iter()
-        .take(galaxies.len() - 1)
-        .enumerate()
-        .flat_map(|(index_a, galaxy_a)| {
-            galaxies
+/// Sum of the distances of all galaxy pairs.
+///
+/// As manhattan distances are independent per axis, each axis is summed up separately: after
+/// sorting, the `i`-th coordinate is the larger one in exactly `i` pairs, so all pairs amount to
+/// `sum(c_i * i - (c_0 + ... + c_(i-1)))`, which is `O(n log n)` instead of `O(n²)`.
+pub fn sum_distances<const D: usize>(universe: &Universe<D>) -> u128 {
+    (0..D)
+        .map(|axis| {
+            let coords = universe
+                .galaxies()
                 .iter()
-                .skip(index_a + 1)
-                .map(|galaxy_b| galaxy_a.distance(galaxy_b))
+                .map(|galaxy| galaxy.coords[axis])
+                .collect();
+            sum_axis_distances(coords)
         })
         .sum()
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Galaxy {
-    x: usize,
-    y: usize,
-}
-
-impl Galaxy {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
+fn sum_axis_distances(mut coords: Vec<u128>) -> u128 {
+    coords.sort_unstable();
 
-    fn distance(&self, other: &Galaxy) -> usize {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    let mut prefix_sum = 0;
+    let mut sum = 0;
+    for (index, coord) in coords.iter().enumerate() {
+        sum += coord * index as u128 - prefix_sum;
+        prefix_sum += coord;
     }
+    sum
 }
 
-pub struct Universe {
-    galaxies: Vec<Galaxy>,
-    size_x: usize,
-    size_y: usize,
+/// Coords are `[x, y]` in 2D, and `[x, y, layer]` in 3D
+#[derive(Debug, Clone, PartialEq)]
+pub struct Galaxy<const D: usize> {
+    coords: [u128; D],
 }
 
-impl Universe {
-    fn is_column_empty(&self, column: usize) -> bool {
-        self.galaxies
-            .iter()
-            .filter(|galaxy| galaxy.x == column)
-            .count()
-            == 0
+impl<const D: usize> Galaxy<D> {
+    fn new(coords: [u128; D]) -> Self {
+        Self { coords }
     }
 
-    fn is_row_empty(&self, row: usize) -> bool {
-        self.galaxies
+    /// manhattan distance over all axes
+    pub fn distance(&self, other: &Self) -> u128 {
+        self.coords
             .iter()
-            .filter(|galaxy| galaxy.y == row)
-            .count()
-            == 0
+            .zip(&other.coords)
+            .map(|(a, b)| a.abs_diff(*b))
+            .sum()
     }
+}
+
+pub struct Universe<const D: usize> {
+    galaxies: Vec<Galaxy<D>>,
+    sizes: [u128; D],
+}
 
-    /// each empty row/col will be expanded by the given `expansion_rate`.
+impl<const D: usize> Universe<D> {
+    /// each empty row/col (or layer) will be expanded by the given `expansion_rate`.
     ///
     /// a rate of 2 means each empty row/col will be doubled. a rate of 1 won't chang anything.
-    pub fn expand(&mut self, expansion_rate: usize) {
+    pub fn expand(&mut self, expansion_rate: u128) {
         assert!(expansion_rate > 0);
 
-        let mut empty_rows = Vec::new();
-        let mut empty_cols = Vec::new();
-
-        for x in 0..self.size_x {
-            if self.is_column_empty(x) {
-                empty_cols.push(x);
-            }
+        for axis in 0..D {
+            let num_empty = expand_axis(
+                self.galaxies
+                    .iter_mut()
+                    .map(|galaxy| &mut galaxy.coords[axis]),
+                self.sizes[axis],
+                expansion_rate,
+            );
+            self.sizes[axis] += num_empty * (expansion_rate - 1);
         }
+    }
 
-        for y in 0..self.size_y {
-            if self.is_row_empty(y) {
-                empty_rows.push(y);
-            }
-        }
+    pub fn galaxies(&self) -> &Vec<Galaxy<D>> {
+        &self.galaxies
+    }
+}
 
-        for galaxy in &mut self.galaxies {
-            let num_empty_cols_before_galaxy = empty_cols.iter().filter(|x| **x < galaxy.x).count();
-            let num_empty_rows_before_galaxy = empty_rows.iter().filter(|y| **y < galaxy.y).count();
-            galaxy.x += num_empty_cols_before_galaxy * (expansion_rate - 1);
-            galaxy.y += num_empty_rows_before_galaxy * (expansion_rate - 1);
-        }
+/// Moves every coordinate by the number of empty lines before it times `expansion_rate - 1`
+/// and returns the number of empty lines, i.e. lines without any of the coordinates.
+fn expand_axis<'a>(
+    coords: impl Iterator<Item = &'a mut u128>,
+    size: u128,
+    expansion_rate: u128,
+) -> u128 {
+    let mut coords: Vec<&mut u128> = coords.collect();
+    let mut occupied: Vec<u128> = coords.iter().map(|coord| **coord).collect();
+    occupied.sort_unstable();
+    occupied.dedup();
 
-        self.size_x += empty_cols.len();
-        self.size_y += empty_rows.len();
+    for coord in &mut coords {
+        let num_occupied_before = occupied.partition_point(|other| *other < **coord) as u128;
+        let num_empty_before = **coord - num_occupied_before;
+        **coord += num_empty_before * (expansion_rate - 1);
     }
 
-    fn galaxies(&self) -> &Vec<Galaxy> {
-        &self.galaxies
+    size - occupied.len() as u128
+}
+
+/// `[x, y]` of all galaxies in a 2D character map, as well as its size
+fn parse_slice(slice: &str) -> (impl Iterator<Item = [u128; 2]> + '_, [u128; 2]) {
+    fn parse_line_with_num((y, line): (usize, &str)) -> impl Iterator<Item = [u128; 2]> + '_ {
+        line.chars()
+            .enumerate()
+            .filter(|(_x, c)| *c == '#')
+            .map(move |(x, _c)| [x as u128, y as u128])
     }
+
+    let galaxies = slice.lines().enumerate().flat_map(parse_line_with_num);
+
+    let size_x = slice.lines().next().unwrap().len() as u128;
+    let size_y = slice.lines().count() as u128;
+
+    (galaxies, [size_x, size_y])
 }
 
-impl FromStr for Universe {
+impl FromStr for Universe<2> {
     type Err = ();
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        fn parse_line_with_num((y, line): (usize, &str)) -> impl Iterator<Item = Galaxy> + '_ {
-            line.chars()
-                .enumerate()
-                .filter(|(_x, c)| *c == '#')
-                .map(move |(x, _c)| Galaxy::new(x, y))
-        }
+        let (galaxies, sizes) = parse_slice(input);
+        let galaxies = galaxies.map(Galaxy::new).collect();
+        Ok(Self { galaxies, sizes })
+    }
+}
 
-        let galaxies: Vec<_> = input
-            .lines()
-            .enumerate()
-            .flat_map(parse_line_with_num)
-            .collect();
+impl Universe<3> {
+    /// Stacks 2D slices separated by blank lines on top of each other, all of the same size.
+    /// Additional blank lines, e.g. at the end of the input, don't add empty layers.
+    pub fn from_layers(input: &str) -> Result<Self, ()> {
+        let mut galaxies = Vec::new();
+        let mut slice_sizes = None;
+        let mut num_layers = 0;
 
-        let size_x = input.lines().next().unwrap().len();
-        let size_y = input.lines().count();
+        let slices = input
+            .split("\n\n")
+            .map(|slice| slice.trim_matches('\n'))
+            .filter(|slice| !slice.is_empty());
+        for (layer, slice) in slices.enumerate() {
+            let (slice_galaxies, sizes) = parse_slice(slice);
+            if *slice_sizes.get_or_insert(sizes) != sizes {
+                return Err(());
+            }
+            galaxies.extend(slice_galaxies.map(|[x, y]| Galaxy::new([x, y, layer as u128])));
+            num_layers += 1;
+        }
 
+        let [size_x, size_y] = slice_sizes.ok_or(())?;
         Ok(Self {
             galaxies,
-            size_x,
-            size_y,
+            sizes: [size_x, size_y, num_layers],
         })
     }
 }
@@ -141,20 +185,15 @@ mod tests {
         universe.expand(2);
 
         let galaxy = universe.galaxies.pop().unwrap();
-        assert_eq!(galaxy.x, 0);
-        assert_eq!(galaxy.y, 6);
+        assert_eq!(galaxy.coords, [0, 6]);
         let galaxy = universe.galaxies.pop().unwrap();
-        assert_eq!(galaxy.x, 1);
-        assert_eq!(galaxy.y, 3);
+        assert_eq!(galaxy.coords, [1, 3]);
         let galaxy = universe.galaxies.pop().unwrap();
-        assert_eq!(galaxy.x, 4);
-        assert_eq!(galaxy.y, 0);
+        assert_eq!(galaxy.coords, [4, 0]);
         let galaxy = universe.galaxies.pop().unwrap();
-        assert_eq!(galaxy.x, 1);
-        assert_eq!(galaxy.y, 0);
+        assert_eq!(galaxy.coords, [1, 0]);
 
-        assert_eq!(universe.size_x, 5);
-        assert_eq!(universe.size_y, 7);
+        assert_eq!(universe.sizes, [5, 7]);
     }
 
     // #[test]
@@ -203,4 +242,86 @@ mod tests {
         let universe = Universe::from_str(input).unwrap();
         assert_eq!(sum_distances(&universe), 374);
     }
+
+    #[test]
+    fn test_sum_distances_matches_pairwise() {
+        let input = crate::read_input();
+        let mut universe = Universe::from_str(&input).unwrap();
+        universe.expand(3);
+
+        let galaxies = universe.galaxies();
+        let mut pairwise = 0;
+        for (index, galaxy_a) in galaxies.iter().enumerate() {
+            for galaxy_b in &galaxies[index + 1..] {
+                pairwise += galaxy_a.distance(galaxy_b);
+            }
+        }
+        assert_eq!(sum_distances(&universe), pairwise);
+    }
+
+    #[test]
+    fn test_distance_3d() {
+        let galaxy_a = Galaxy::new([1, 5, 2]);
+        let galaxy_b = Galaxy::new([4, 0, 2]);
+        assert_eq!(galaxy_a.distance(&galaxy_b), 8);
+    }
+
+    #[test]
+    fn test_layers_expand() {
+        let input = "\
+            #..\n\
+            ...\n\
+            \n\
+            ...\n\
+            ...\n\
+            \n\
+            ..#\n\
+            .#.";
+        let mut universe = Universe::from_layers(input).unwrap();
+        assert_eq!(universe.sizes, [3, 2, 3]);
+
+        universe.expand(10);
+        let coords: Vec<[u128; 3]> = universe
+            .galaxies
+            .iter()
+            .map(|galaxy| galaxy.coords)
+            .collect();
+        assert_eq!(coords, [[0, 0, 0], [2, 0, 11], [1, 1, 11]]);
+        assert_eq!(universe.sizes, [3, 2, 12]);
+        assert_eq!(sum_distances(&universe), 13 + 13 + 2);
+    }
+
+    #[test]
+    fn test_layers_of_different_size() {
+        let input = "\
+            #..\n\
+            \n\
+            ..";
+        assert!(Universe::from_layers(input).is_err());
+    }
+
+    #[test]
+    fn test_layers_with_blank_lines() {
+        let input = "\
+            #.\n\
+            \n\
+            \n\
+            .#\n\
+            \n";
+        let universe = Universe::from_layers(input).unwrap();
+        assert_eq!(universe.sizes, [2, 1, 2]);
+        assert_eq!(sum_distances(&universe), 2);
+
+        assert!(Universe::from_layers("\n\n").is_err());
+    }
+
+    #[test]
+    fn test_single_layer_equals_2d() {
+        let input = crate::read_input();
+        let mut universe_2d = Universe::from_str(&input).unwrap();
+        let mut universe_3d = Universe::from_layers(&input).unwrap();
+        universe_2d.expand(1_000_000);
+        universe_3d.expand(1_000_000);
+        assert_eq!(sum_distances(&universe_2d), sum_distances(&universe_3d));
+    }
 }
@@ -1,16 +1,15 @@
-use crate::part1::{sum_distances, Universe};
+use crate::part1::{sum_expanded_distances, Universe};
 
 use std::str::FromStr;
 
-pub fn run(input: &str) -> usize {
-    let mut universe = Universe::from_str(input).unwrap();
-    universe.expand(1_000_000);
-    sum_distances(&universe)
+pub fn run(input: &str) -> u128 {
+    sum_expanded_distances(Universe::from_str(input).unwrap(), 1_000_000)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::part1::sum_distances;
 
     const EXAMPLE: &'static str = "\
         ...#......\n\
@@ -37,4 +36,12 @@ mod tests {
         universe.expand(100);
         assert_eq!(sum_distances(&universe), 8410);
     }
+
+    #[test]
+    fn test_example_with_huge_rate() {
+        let rate = 1_000_000_000_000;
+        let mut universe = Universe::from_str(EXAMPLE).unwrap();
+        universe.expand(rate);
+        assert_eq!(sum_distances(&universe), 292 + 82 * (rate - 1));
+    }
 }
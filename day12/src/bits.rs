@@ -0,0 +1,83 @@
+/// Growable bit vector, bit `0` is the first one pushed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+impl BitVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, bit);
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len);
+        self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, bit: bool) {
+        assert!(index < self.len);
+        let word = &mut self.words[index / WORD_BITS];
+        let mask = 1 << (index % WORD_BITS);
+        if bit {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// index of the first set bit
+    pub fn first_one(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .find(|(_, word)| **word != 0)
+            .map(|(index, word)| index * WORD_BITS + word.trailing_zeros() as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_get() {
+        let mut bits = BitVec::new();
+        for index in 0..200 {
+            bits.push(index % 3 == 0);
+        }
+        assert_eq!(bits.len(), 200);
+        assert!(bits.get(0));
+        assert!(!bits.get(130));
+        assert!(bits.get(198));
+    }
+
+    #[test]
+    fn test_first_one() {
+        let mut bits = BitVec::new();
+        for _ in 0..150 {
+            bits.push(false);
+        }
+        assert_eq!(bits.first_one(), None);
+
+        bits.set(140, true);
+        bits.set(70, true);
+        assert_eq!(bits.first_one(), Some(70));
+        bits.set(70, false);
+        assert_eq!(bits.first_one(), Some(140));
+    }
+}
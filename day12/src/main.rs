@@ -1,6 +1,10 @@
+mod bits;
+mod nonogram;
 mod part1;
 mod part2;
 
+use timings::Stage;
+
 pub fn read_input() -> String {
     use std::fs;
 
@@ -9,7 +13,57 @@ pub fn read_input() -> String {
 }
 
 fn main() {
+    // `day12 arrangements "<record>" [limit]` lists the arrangements of a single record
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("arrangements") {
+        let record: part2::Record = args[1].parse().unwrap();
+        let limit = args
+            .get(2)
+            .map_or(usize::MAX, |limit| limit.parse().unwrap());
+        for arrangement in record.arrangements().take(limit) {
+            println!("{arrangement}");
+        }
+        println!("forced springs: {:?}", record.forced_springs());
+        return;
+    }
+
+    // `day12 nonogram <file>` solves a nonogram with row and column clues
+    if args.first().map(String::as_str) == Some("nonogram") {
+        let input = std::fs::read_to_string(&args[1]).unwrap();
+        let nonogram: nonogram::Nonogram = input.parse().unwrap();
+        match nonogram.solve() {
+            nonogram::Solutions::None => println!("no solution"),
+            nonogram::Solutions::Unique(grid) => print!("{}", nonogram::format_grid(&grid)),
+            nonogram::Solutions::Many(first, second) => {
+                println!("many solutions, e.g.");
+                println!("{}", nonogram::format_grid(&first));
+                print!("{}", nonogram::format_grid(&second));
+            }
+        }
+        return;
+    }
+
+    // `day12 unfold <factor>` runs part 2 with another unfolding factor
+    if args.first().map(String::as_str) == Some("unfold") {
+        let factor = args[1].parse().unwrap();
+        let result = part2::run_with_unfolding(&read_input(), factor);
+        println!("Result with unfolding {factor} is {result}");
+        return;
+    }
+
     let input = read_input();
+
+    // `day12 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || (part1::parse(&input), part2::parse(&input))),
+        Stage::with_setup("part1", || part1::parse(&input), part1::count_arrangements),
+        Stage::with_setup(
+            "part2",
+            || part2::parse(&input),
+            |records| part2::count_unfolded_arrangements(records, 5),
+        ),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
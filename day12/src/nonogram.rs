@@ -0,0 +1,227 @@
+use crate::part2::{Record, Spring};
+
+use std::str::FromStr;
+
+/// `[y][x]`, with damaged springs as filled cells and ok springs as empty ones
+pub type Grid = Vec<Vec<Spring>>;
+
+#[derive(Debug, PartialEq)]
+pub enum Solutions {
+    None,
+    Unique(Grid),
+    /// the first two solutions that were found
+    Many(Grid, Grid),
+}
+
+/// A nonogram is a grid where each row and each column is a record of damaged springs without
+/// any known springs in the beginning.
+pub struct Nonogram {
+    rows: Vec<Vec<usize>>,
+    columns: Vec<Vec<usize>>,
+}
+
+impl FromStr for Nonogram {
+    type Err = ();
+
+    /// One clue per line, row clues first, then a blank line and the column clues. Empty lines
+    /// are written as `0`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        fn parse_clues(block: &str) -> Result<Vec<Vec<usize>>, ()> {
+            block
+                .lines()
+                .map(|line| {
+                    line.split(',')
+                        .map(|x| x.trim().parse().map_err(|_| ()))
+                        .filter(|x| *x != Ok(0))
+                        .collect()
+                })
+                .collect()
+        }
+
+        let (rows, columns) = input.split_once("\n\n").ok_or(())?;
+        Ok(Self::new(parse_clues(rows)?, parse_clues(columns)?))
+    }
+}
+
+impl Nonogram {
+    pub fn new(rows: Vec<Vec<usize>>, columns: Vec<Vec<usize>>) -> Self {
+        Self { rows, columns }
+    }
+
+    /// Searches for up to two solutions, which is enough to know whether it is unique.
+    pub fn solve(&self) -> Solutions {
+        let grid = vec![vec![Spring::Unknown; self.columns.len()]; self.rows.len()];
+        let mut solutions = Vec::new();
+        self.search(grid, &mut solutions);
+
+        let mut solutions = solutions.into_iter();
+        match (solutions.next(), solutions.next()) {
+            (None, _) => Solutions::None,
+            (Some(solution), None) => Solutions::Unique(solution),
+            (Some(first), Some(second)) => Solutions::Many(first, second),
+        }
+    }
+
+    fn search(&self, mut grid: Grid, solutions: &mut Vec<Grid>) {
+        if solutions.len() >= 2 || self.propagate(&mut grid).is_err() {
+            return;
+        }
+
+        let unknown = grid.iter().enumerate().find_map(|(y, row)| {
+            let x = row.iter().position(|cell| *cell == Spring::Unknown)?;
+            Some((x, y))
+        });
+        let Some((x, y)) = unknown else {
+            // every line was checked against its clue while propagating
+            solutions.push(grid);
+            return;
+        };
+
+        // line solving got stuck, so guess
+        for guess in [Spring::Damaged, Spring::Ok] {
+            let mut guessed_grid = grid.clone();
+            guessed_grid[y][x] = guess;
+            self.search(guessed_grid, solutions);
+        }
+    }
+
+    /// Fills in all cells forced by a single line until nothing changes anymore, fails if any
+    /// line contradicts its clue.
+    fn propagate(&self, grid: &mut Grid) -> Result<(), ()> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for (y, clue) in self.rows.iter().enumerate() {
+                let line = grid[y].clone();
+                for (x, spring) in Self::solve_line(line, clue)? {
+                    grid[y][x] = spring;
+                    changed = true;
+                }
+            }
+
+            for (x, clue) in self.columns.iter().enumerate() {
+                let line = grid.iter().map(|row| row[x]).collect();
+                for (y, spring) in Self::solve_line(line, clue)? {
+                    grid[y][x] = spring;
+                    changed = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn solve_line(line: Vec<Spring>, clue: &[usize]) -> Result<Vec<(usize, Spring)>, ()> {
+        Record::new(line, clue.to_vec()).forced_springs().ok_or(())
+    }
+}
+
+pub fn format_grid(grid: &Grid) -> String {
+    grid.iter()
+        .map(|row| row.iter().map(|cell| format!("{cell}")).collect::<String>() + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clue(line: &[Spring]) -> Vec<usize> {
+        line.split(|spring| *spring == Spring::Ok)
+            .map(|group| group.len())
+            .filter(|len| *len > 0)
+            .collect()
+    }
+
+    fn clues_of(grid: &Grid) -> Nonogram {
+        let rows = grid.iter().map(|row| clue(row)).collect();
+        let columns = (0..grid[0].len())
+            .map(|x| clue(&grid.iter().map(|row| row[x]).collect::<Vec<_>>()))
+            .collect();
+        Nonogram::new(rows, columns)
+    }
+
+    #[test]
+    fn test_unique() {
+        let input = "1\n1\n5\n1\n1\n\n1\n1\n5\n1\n1";
+        let nonogram = Nonogram::from_str(input).unwrap();
+        let Solutions::Unique(grid) = nonogram.solve() else {
+            panic!("expected a unique solution");
+        };
+        assert_eq!(format_grid(&grid), "..#..\n..#..\n#####\n..#..\n..#..\n");
+    }
+
+    #[test]
+    fn test_unique_with_empty_lines() {
+        let input = "\
+            3\n\
+            0\n\
+            1,1\n\
+            \n\
+            1,1\n\
+            1\n\
+            1,1";
+        let nonogram = Nonogram::from_str(input).unwrap();
+        let Solutions::Unique(grid) = nonogram.solve() else {
+            panic!("expected a unique solution");
+        };
+        assert_eq!(format_grid(&grid), "###\n...\n#.#\n");
+    }
+
+    #[test]
+    fn test_many() {
+        let nonogram = Nonogram::new(vec![vec![1], vec![1]], vec![vec![1], vec![1]]);
+        let Solutions::Many(first, second) = nonogram.solve() else {
+            panic!("expected many solutions");
+        };
+        assert_eq!(format_grid(&first), "#.\n.#\n");
+        assert_eq!(format_grid(&second), ".#\n#.\n");
+    }
+
+    #[test]
+    fn test_none() {
+        let nonogram = Nonogram::new(vec![vec![1]], vec![vec![]]);
+        assert_eq!(nonogram.solve(), Solutions::None);
+
+        let nonogram = Nonogram::new(vec![vec![2], vec![]], vec![vec![2], vec![]]);
+        assert_eq!(nonogram.solve(), Solutions::None);
+    }
+
+    #[test]
+    fn test_solutions_match_clues() {
+        // pseudo random 8x8 pictures, which need guessing more often than not
+        let mut seed: u64 = 42;
+        for _ in 0..20 {
+            let grid: Grid = (0..8)
+                .map(|_| {
+                    (0..8)
+                        .map(|_| {
+                            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                            match seed >> 63 {
+                                0 => Spring::Ok,
+                                _ => Spring::Damaged,
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            let nonogram = clues_of(&grid);
+
+            let solution = match nonogram.solve() {
+                Solutions::None => panic!("the original picture is a solution"),
+                Solutions::Unique(solution) => {
+                    assert_eq!(solution, grid);
+                    solution
+                }
+                Solutions::Many(first, second) => {
+                    assert_ne!(first, second);
+                    assert_eq!(clues_of(&second).rows, nonogram.rows);
+                    first
+                }
+            };
+            let solved = clues_of(&solution);
+            assert_eq!(solved.rows, nonogram.rows);
+            assert_eq!(solved.columns, nonogram.columns);
+        }
+    }
+}
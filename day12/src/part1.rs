@@ -1,15 +1,26 @@
-use std::cmp;
+use crate::bits::BitVec;
+
 use std::str::FromStr;
 
 pub fn run(input: &str) -> usize {
-    input.lines().map(count_per_line).sum()
+    count_arrangements(parse(input))
 }
 
-fn count_per_line(line: &str) -> usize {
-    let springs = BitSprings::from_str(line).unwrap();
-    let groups = GroupSprings::from_str(line).unwrap();
+pub fn parse(input: &str) -> Vec<(BitSprings, GroupSprings)> {
+    input
+        .lines()
+        .map(|line| {
+            let springs = BitSprings::from_str(line).unwrap();
+            let groups = GroupSprings::from_str(line).unwrap();
+            (springs, groups)
+        })
+        .collect()
+}
 
-    springs.count_possible_arrangements(groups)
+pub fn count_arrangements(rows: Vec<(BitSprings, GroupSprings)>) -> usize {
+    rows.into_iter()
+        .map(|(springs, groups)| springs.count_possible_arrangements(groups))
+        .sum()
 }
 
 #[derive(Clone)]
@@ -55,43 +66,48 @@ impl GroupSprings {
     }
 
     pub fn validate(&self, springs: &BitSprings) -> Result<(), ()> {
-        struct Checker {
-            buf: u128,
-            num_remaining: usize,
+        /// Reads the known springs in front of the first unknown one, with 1 extra working
+        /// spring at the beginning, so the good spring check also passes for the first group.
+        struct Checker<'a> {
+            broken: &'a BitVec,
+            position: usize,
+            end: usize,
             num_ignored: usize,
         }
-        impl Checker {
-            fn new(springs: &BitSprings) -> Self {
-                let mut num_remaining = cmp::min(
-                    springs.num_springs,
-                    springs.unknown_mask.trailing_zeros() as usize,
-                );
-                let num_missing = springs.num_springs - num_remaining;
-
-                // mask away unknown springs
-                let known_mask = (1 << num_remaining) - 1;
-                let mut buf = springs.broken_mask & known_mask;
-
-                // add 1 working spring at the beginning, to not trigger the good spring check
-                buf <<= 1;
-                num_remaining += 1;
+        impl<'a> Checker<'a> {
+            fn new(springs: &'a BitSprings) -> Self {
+                let num_springs = springs.num_springs();
+                let num_known = springs.unknown.first_one().unwrap_or(num_springs);
 
                 Self {
-                    buf,
-                    num_remaining,
-                    num_ignored: num_missing,
+                    broken: &springs.broken,
+                    position: 0,
+                    end: num_known + 1,
+                    num_ignored: num_springs - num_known,
                 }
             }
 
+            fn is_broken(&self, index: usize) -> bool {
+                index > 0 && self.broken.get(index - 1)
+            }
+
+            fn num_remaining(&self) -> usize {
+                self.end - self.position
+            }
+
+            fn count_remaining(&self, broken: bool) -> usize {
+                (self.position..self.end)
+                    .take_while(|index| self.is_broken(*index) == broken)
+                    .count()
+            }
+
             fn shift_out(&mut self, num: usize) {
-                assert!(self.num_remaining >= num);
-                self.num_remaining -= num;
-                self.buf >>= num;
+                assert!(self.num_remaining() >= num);
+                self.position += num;
             }
 
             fn check_good_before_group(&mut self) -> Result<(), ()> {
-                let num_good_springs =
-                    cmp::min(self.num_remaining, self.buf.trailing_zeros() as usize);
+                let num_good_springs = self.count_remaining(false);
 
                 // before each group, there has to be at least one good spring. because of
                 // the constructor, this is also the case for the first group.
@@ -106,7 +122,7 @@ impl GroupSprings {
             }
 
             fn check_only_good_remaining(&self) -> Result<(), ()> {
-                if self.buf != 0 {
+                if (self.position..self.end).any(|index| self.is_broken(index)) {
                     Err(())
                 } else {
                     Ok(())
@@ -114,8 +130,7 @@ impl GroupSprings {
             }
 
             fn check_group(&mut self, group: usize) -> Result<(), ()> {
-                // min with remaining not needed, as rest is 0 and won't be counted
-                let num_bad_springs = self.buf.trailing_ones() as usize;
+                let num_bad_springs = self.count_remaining(true);
 
                 if num_bad_springs == group {
                     self.shift_out(num_bad_springs);
@@ -123,23 +138,23 @@ impl GroupSprings {
                 }
 
                 // if group fits into remaining, then the bits are wrong
-                if self.num_remaining >= group {
+                if self.num_remaining() >= group {
                     return Err(());
                 }
 
                 // if group does not fit into the rest (including ignored), the bits are wrong
-                if group > self.num_remaining + self.num_ignored {
+                if group > self.num_remaining() + self.num_ignored {
                     return Err(());
                 }
 
                 // if group just does not fit into the remaining ones, but there would still be
                 // enough that are ignored, we are just not finished yet
-                self.num_remaining = 0;
+                self.position = self.end;
                 Ok(())
             }
 
             fn none_remaining(&self) -> bool {
-                self.num_remaining == 0
+                self.num_remaining() == 0
             }
 
             fn none_ignored(&self) -> bool {
@@ -190,25 +205,19 @@ impl GroupSprings {
 
 #[derive(Clone)]
 pub struct BitSprings {
-    broken_mask: u128,
-    unknown_mask: u128,
-    num_springs: usize,
+    broken: BitVec,
+    unknown: BitVec,
 }
 
 impl std::fmt::Debug for BitSprings {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut broken = self.broken_mask;
-        let mut unknown = self.unknown_mask;
-        for _ in 0..self.num_springs {
-            let spring = match (broken & 0b_1, unknown & 0b_1) {
-                (0, 0) => '.',
-                (1, 0) => '#',
-                (_, 1) => '?',
-                _ => unreachable!(),
+        for index in 0..self.num_springs() {
+            let spring = match (self.broken.get(index), self.unknown.get(index)) {
+                (false, false) => '.',
+                (true, false) => '#',
+                (_, true) => '?',
             };
             write!(f, "{spring}")?;
-            broken >>= 1;
-            unknown >>= 1;
         }
         Ok(())
     }
@@ -218,71 +227,46 @@ impl FromStr for BitSprings {
     type Err = ();
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let parse_broken_mask = |spring| match spring {
-            '?' => 0,
-            '#' => 1,
-            '.' => 0,
-            _ => panic!("unknown spring symbol"),
-        };
-        let parse_unknown_mask = |spring| match spring {
-            '?' => 1,
-            '#' => 0,
-            '.' => 0,
-            _ => panic!("unknown spring symbol"),
-        };
-        fn parse(spring_str: &str, parse_func: impl Fn(char) -> u128) -> u128 {
-            spring_str
-                .chars()
-                .map(parse_func)
-                .enumerate()
-                .map(|(index, spring)| spring << index)
-                .fold(0, |buf, spring| buf | spring)
+        let spring_str = line.split_once(' ').ok_or(())?.0;
+
+        let mut broken = BitVec::new();
+        let mut unknown = BitVec::new();
+        for spring in spring_str.chars() {
+            let (is_broken, is_unknown) = match spring {
+                '?' => (false, true),
+                '#' => (true, false),
+                '.' => (false, false),
+                _ => panic!("unknown spring symbol"),
+            };
+            broken.push(is_broken);
+            unknown.push(is_unknown);
         }
 
-        let spring_str = line.split_once(' ').ok_or(())?.0;
-        let num_springs = spring_str.chars().count();
-        let broken_mask = parse(spring_str, parse_broken_mask);
-        let unknown_mask = parse(spring_str, parse_unknown_mask);
-
-        Ok(Self {
-            broken_mask,
-            unknown_mask,
-            num_springs,
-        })
+        Ok(Self { broken, unknown })
     }
 }
 
 impl BitSprings {
+    fn num_springs(&self) -> usize {
+        self.broken.len()
+    }
+
     #[allow(dead_code)] // part2 uses different solution now
     pub fn unfold(&mut self, factor: usize) {
-        if (self.num_springs + 1) * factor > 128 {
-            panic!("factor is too big for this spring, won't fit in the representation!");
-        }
-
-        if factor == 0 {
-            self.broken_mask = 0;
-            self.unknown_mask = 0;
-            self.num_springs = 0;
-        }
-
-        let old_broken = self.broken_mask;
-        let old_unknown = self.unknown_mask;
-        let old_num = self.num_springs;
-
-        for _ in 0..factor - 1 {
-            // first shift in 1x '?'
-            self.broken_mask <<= 1;
-            self.broken_mask |= 0;
-            self.unknown_mask <<= 1;
-            self.unknown_mask |= 1;
-            self.num_springs += 1;
-
-            // then shift in old mask
-            self.broken_mask <<= old_num;
-            self.broken_mask |= old_broken;
-            self.unknown_mask <<= old_num;
-            self.unknown_mask |= old_unknown;
-            self.num_springs += old_num;
+        let old = self.clone();
+        self.broken = BitVec::new();
+        self.unknown = BitVec::new();
+
+        for copy in 0..factor {
+            // separate the copies with 1x '?'
+            if copy > 0 {
+                self.broken.push(false);
+                self.unknown.push(true);
+            }
+            for index in 0..old.num_springs() {
+                self.broken.push(old.broken.get(index));
+                self.unknown.push(old.unknown.get(index));
+            }
         }
     }
 
@@ -307,27 +291,13 @@ impl BitSprings {
     }
 
     pub fn collapse_next(self) -> Option<(Self, Self)> {
-        if self.unknown_mask == 0 {
-            return None;
-        }
+        let index = self.unknown.first_one()?;
 
-        let index = self.unknown_mask.trailing_zeros();
-        let spring_mask = 1 << index;
-        let unknown_mask = self.unknown_mask & !spring_mask;
-        let broken_mask_a = self.broken_mask & !spring_mask;
-        let broken_mask_b = self.broken_mask | spring_mask;
-        let num_springs = self.num_springs;
-
-        let onsen_a = Self {
-            broken_mask: broken_mask_a,
-            unknown_mask,
-            num_springs,
-        };
-        let onsen_b = Self {
-            broken_mask: broken_mask_b,
-            unknown_mask,
-            num_springs,
-        };
+        let mut onsen_a = self;
+        onsen_a.unknown.set(index, false);
+        onsen_a.broken.set(index, false);
+        let mut onsen_b = onsen_a.clone();
+        onsen_b.broken.set(index, true);
 
         Some((onsen_a, onsen_b))
     }
@@ -337,6 +307,10 @@ impl BitSprings {
 mod tests {
     use super::*;
 
+    fn count_per_line(line: &str) -> usize {
+        run(line)
+    }
+
     #[test]
     fn test_onsen_collapse_none() {
         let line = ".# foo";
@@ -436,11 +410,23 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    fn test_unfold_springs_panics_if_too_long() {
-        let line = "...............";
+    fn test_unfold_beyond_128_springs() {
+        let line = "#. 1";
+
+        let mut springs = BitSprings::from_str(line).unwrap();
+        springs.unfold(50);
+        let expected = vec!["#."; 50].join("?");
+        assert_eq!(format!("{springs:?}"), expected);
 
         let mut groups = GroupSprings::from_str(line).unwrap();
-        groups.unfold(8);
+        groups.unfold(50);
+        assert_eq!(springs.count_possible_arrangements(groups), 1);
+    }
+
+    #[test]
+    fn test_unfold_zero() {
+        let mut springs = BitSprings::from_str("?.# 1").unwrap();
+        springs.unfold(0);
+        assert_eq!(format!("{springs:?}"), "");
     }
 }
@@ -1,19 +1,36 @@
+use num::{BigUint, One, Zero};
+
 use std::collections::HashMap;
 use std::iter;
 use std::str::FromStr;
 
-pub fn run(input: &str) -> usize {
-    input.lines().map(count_per_line_with_unfolding).sum()
+pub fn run(input: &str) -> BigUint {
+    run_with_unfolding(input, 5)
+}
+
+pub fn run_with_unfolding(input: &str, factor: usize) -> BigUint {
+    count_unfolded_arrangements(parse(input), factor)
+}
+
+pub fn parse(input: &str) -> Vec<Record> {
+    input
+        .lines()
+        .map(|line| Record::from_str(line).unwrap())
+        .collect()
 }
 
-fn count_per_line_with_unfolding(line: &str) -> usize {
-    let mut record = Record::from_str(line).unwrap();
-    record.unfold(5);
-    record.count_possible_arrangements()
+pub fn count_unfolded_arrangements(records: Vec<Record>, factor: usize) -> BigUint {
+    records
+        .into_iter()
+        .map(|mut record| {
+            record.unfold(factor);
+            record.count_possible_arrangements()
+        })
+        .sum()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Spring {
+pub enum Spring {
     Ok,
     Damaged,
     Unknown,
@@ -43,8 +60,8 @@ impl std::fmt::Display for Spring {
     }
 }
 
-#[derive(Debug)]
-struct Record {
+#[derive(Debug, Clone)]
+pub struct Record {
     springs: Vec<Spring>,
     counts: Vec<usize>,
 }
@@ -77,24 +94,70 @@ impl std::fmt::Display for Record {
 }
 
 impl Record {
-    fn count_possible_arrangements(&self) -> usize {
+    pub fn new(springs: Vec<Spring>, counts: Vec<usize>) -> Self {
+        Self { springs, counts }
+    }
+
+    pub fn count_possible_arrangements(&self) -> BigUint {
         let mut cache = Cache::new();
         Self::possible_arrangements_inner(&self.springs, &self.counts, &mut cache)
     }
 
     fn unfold(&mut self, factor: usize) {
-        self.springs = iter::repeat(iter::once(&Spring::Unknown).chain(self.springs.iter()))
-            .take(factor)
+        self.springs = iter::repeat_n(
+            iter::once(&Spring::Unknown).chain(self.springs.iter()),
+            factor,
+        )
+        .flatten()
+        .skip(1)
+        .copied()
+        .collect();
+
+        self.counts = iter::repeat_n(self.counts.iter(), factor)
             .flatten()
-            .skip(1)
             .copied()
             .collect();
+    }
 
-        self.counts = iter::repeat(self.counts.iter())
-            .take(factor)
-            .flatten()
-            .copied()
+    /// Lazily yields every arrangement as `#`/`.` string, in lexicographic order (so `#` before
+    /// `.`). Use `take` to only get the first few.
+    pub fn arrangements(&self) -> Arrangements<'_> {
+        Arrangements {
+            record: self,
+            stack: vec![(0, 0, String::with_capacity(self.springs.len()))],
+            cache: Cache::new(),
+        }
+    }
+
+    /// All unknown springs that are the same in every arrangement, with the state they have
+    /// there. Returns `None` if there is no arrangement at all.
+    pub fn forced_springs(&self) -> Option<Vec<(usize, Spring)>> {
+        if self.count_possible_arrangements().is_zero() {
+            return None;
+        }
+
+        let count_with = |index: usize, spring: Spring| {
+            let mut record = self.clone();
+            record.springs[index] = spring;
+            record.count_possible_arrangements()
+        };
+
+        let forced = self
+            .springs
+            .iter()
+            .enumerate()
+            .filter(|(_, spring)| **spring == Spring::Unknown)
+            .filter_map(|(index, _)| {
+                if count_with(index, Spring::Damaged).is_zero() {
+                    Some((index, Spring::Ok))
+                } else if count_with(index, Spring::Ok).is_zero() {
+                    Some((index, Spring::Damaged))
+                } else {
+                    None
+                }
+            })
             .collect();
+        Some(forced)
     }
 
     /// invariant: spring position has to be at beginning of damaged block
@@ -140,7 +203,7 @@ impl Record {
         springs: &[Spring],
         counts: &[usize],
         cache: &mut Cache,
-    ) -> usize {
+    ) -> BigUint {
         if let Some(cached_val) = cache.get(springs, counts) {
             return cached_val;
         }
@@ -148,9 +211,9 @@ impl Record {
         if springs.is_empty() || counts.is_empty() {
             // we reached the end, so if this is a valid combination, return count of 1
             return if Self::is_valid_arrangement(springs, counts) {
-                1
+                BigUint::one()
             } else {
-                0
+                BigUint::zero()
             };
         }
 
@@ -158,7 +221,7 @@ impl Record {
         let current_spring = springs[0];
         let current_count = counts[0];
 
-        let mut result = 0;
+        let mut result = BigUint::zero();
 
         // assume spring is ok
         if current_spring == Spring::Ok || current_spring == Spring::Unknown {
@@ -181,15 +244,69 @@ impl Record {
             }
         }
 
-        cache.add(springs, counts, result);
+        cache.add(springs, counts, result.clone());
 
         result
     }
 }
 
+/// Depth first search through the same decisions as
+/// [`Record::possible_arrangements_inner`], which also prunes all branches without any
+/// arrangement, so every visited branch yields at least one.
+pub struct Arrangements<'a> {
+    record: &'a Record,
+    /// next spring index, next count index, and the arrangement up to the spring index
+    stack: Vec<(usize, usize, String)>,
+    cache: Cache,
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((spring_index, count_index, mut arrangement)) = self.stack.pop() {
+            let springs = &self.record.springs[spring_index..];
+            let counts = &self.record.counts[count_index..];
+
+            if Record::possible_arrangements_inner(springs, counts, &mut self.cache).is_zero() {
+                continue;
+            }
+
+            if counts.is_empty() {
+                // only ok springs remaining, which is valid as we have arrangements
+                arrangement.extend(iter::repeat_n('.', springs.len()));
+                return Some(arrangement);
+            }
+
+            // push the ok spring first, so the damaged one is popped first
+            if springs[0] != Spring::Damaged {
+                let mut ok_arrangement = arrangement.clone();
+                ok_arrangement.push('.');
+                self.stack
+                    .push((spring_index + 1, count_index, ok_arrangement));
+            }
+
+            if springs[0] != Spring::Ok && Record::is_valid_arrangement(springs, counts) {
+                let current_count = counts[0];
+                arrangement.extend(iter::repeat_n('#', current_count));
+                let mut next_spring_index = spring_index + current_count;
+                if next_spring_index < self.record.springs.len() {
+                    arrangement.push('.');
+                    next_spring_index += 1;
+                }
+                self.stack
+                    .push((next_spring_index, count_index + 1, arrangement));
+            }
+        }
+        None
+    }
+}
+
+/// Memo of [`Record::possible_arrangements_inner`]. It is only ever called with suffixes of the
+/// same springs and counts, so their lengths identify the arguments.
 #[derive(Debug, Default)]
 struct Cache {
-    data: HashMap<u16, usize>,
+    data: HashMap<(usize, usize), BigUint>,
 }
 
 impl Cache {
@@ -197,18 +314,18 @@ impl Cache {
         Self::default()
     }
 
-    fn calc_key(springs: &[Spring], counts: &[usize]) -> u16 {
-        (springs.len() as u16) | ((counts.len() as u16) << 8)
+    fn calc_key(springs: &[Spring], counts: &[usize]) -> (usize, usize) {
+        (springs.len(), counts.len())
     }
 
-    fn add(&mut self, springs: &[Spring], counts: &[usize], result: usize) {
+    fn add(&mut self, springs: &[Spring], counts: &[usize], result: BigUint) {
         let key = Self::calc_key(springs, counts);
         self.data.insert(key, result);
     }
 
-    fn get(&self, springs: &[Spring], counts: &[usize]) -> Option<usize> {
+    fn get(&self, springs: &[Spring], counts: &[usize]) -> Option<BigUint> {
         let key = Self::calc_key(springs, counts);
-        self.data.get(&key).copied()
+        self.data.get(&key).cloned()
     }
 }
 
@@ -216,6 +333,10 @@ impl Cache {
 mod tests {
     use super::*;
 
+    fn count_per_line_with_unfolding(line: &str, factor: usize) -> BigUint {
+        run_with_unfolding(line, factor)
+    }
+
     #[test]
     fn test_unfolding() {
         let line = "??.## 1,2";
@@ -236,7 +357,7 @@ mod tests {
         let mut cache = Cache::new();
         assert_eq!(
             Record::possible_arrangements_inner(&springs, &counts, &mut cache),
-            1
+            BigUint::one()
         );
     }
 
@@ -247,7 +368,7 @@ mod tests {
         let mut cache = Cache::new();
         assert_eq!(
             Record::possible_arrangements_inner(&springs, &counts, &mut cache),
-            1
+            BigUint::one()
         );
     }
 
@@ -255,42 +376,143 @@ mod tests {
     fn test_example_without_unfolding() {
         let line = "???.### 1,1,3";
         let record = Record::from_str(line).unwrap();
-        assert_eq!(record.count_possible_arrangements(), 1);
+        assert_eq!(record.count_possible_arrangements(), BigUint::from(1u32));
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let record = Record::from_str("?###???????? 3,2,1").unwrap();
+        let arrangements: Vec<String> = record.arrangements().collect();
+        assert_eq!(arrangements.len(), 10);
+        assert_eq!(arrangements[0], ".###.##.#...");
+        assert_eq!(arrangements[9], ".###....##.#");
+
+        let mut sorted = arrangements.clone();
+        sorted.sort();
+        assert_eq!(arrangements, sorted);
+    }
+
+    #[test]
+    fn test_arrangements_are_valid() {
+        let record = Record::from_str(".??..??...?##. 1,1,3").unwrap();
+        let arrangements: Vec<String> = record.arrangements().collect();
+        assert_eq!(
+            arrangements,
+            [
+                ".#...#....###.",
+                ".#....#...###.",
+                "..#..#....###.",
+                "..#...#...###."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrangements_with_limit() {
+        let mut record = Record::from_str("?###???????? 3,2,1").unwrap();
+        record.unfold(5);
+        let first: Vec<String> = record.arrangements().take(3).collect();
+        assert_eq!(first.len(), 3);
+        assert!(first[0] < first[1] && first[1] < first[2]);
+    }
+
+    #[test]
+    fn test_no_arrangements() {
+        let record = Record::from_str("#.# 3").unwrap();
+        assert_eq!(record.arrangements().next(), None);
+        assert_eq!(record.forced_springs(), None);
+    }
+
+    #[test]
+    fn test_forced_springs() {
+        let record = Record::from_str("?###???????? 3,2,1").unwrap();
+        let forced = record.forced_springs().unwrap();
+        assert_eq!(forced, [(0, Spring::Ok), (4, Spring::Ok)]);
+
+        let record = Record::from_str("???.### 1,1,3").unwrap();
+        let forced = record.forced_springs().unwrap();
+        assert_eq!(
+            forced,
+            [(0, Spring::Damaged), (1, Spring::Ok), (2, Spring::Damaged)]
+        );
     }
 
     #[test]
     fn test_part2_example1() {
         let line = "???.### 1,1,3";
-        assert_eq!(count_per_line_with_unfolding(line), 1);
+        assert_eq!(count_per_line_with_unfolding(line, 5), BigUint::from(1u32));
     }
 
     #[test]
     fn test_part2_example2() {
         let line = ".??..??...?##. 1,1,3";
-        assert_eq!(count_per_line_with_unfolding(line), 16384);
+        assert_eq!(
+            count_per_line_with_unfolding(line, 5),
+            BigUint::from(16384u32)
+        );
     }
 
     #[test]
     fn test_part2_example3() {
         let line = "?#?#?#?#?#?#?#? 1,3,1,6";
-        assert_eq!(count_per_line_with_unfolding(line), 1);
+        assert_eq!(count_per_line_with_unfolding(line, 5), BigUint::from(1u32));
     }
 
     #[test]
     fn test_part2_example4() {
         let line = "????.#...#... 4,1,1";
-        assert_eq!(count_per_line_with_unfolding(line), 16);
+        assert_eq!(count_per_line_with_unfolding(line, 5), BigUint::from(16u32));
     }
 
     #[test]
     fn test_part2_example5() {
         let line = "????.######..#####. 1,6,5";
-        assert_eq!(count_per_line_with_unfolding(line), 2500);
+        assert_eq!(
+            count_per_line_with_unfolding(line, 5),
+            BigUint::from(2500u32)
+        );
     }
 
     #[test]
     fn test_part2_example6() {
         let line = "?###???????? 3,2,1";
-        assert_eq!(count_per_line_with_unfolding(line), 506250);
+        assert_eq!(
+            count_per_line_with_unfolding(line, 5),
+            BigUint::from(506250u32)
+        );
+    }
+
+    fn pow(base: u32, exponent: u32) -> BigUint {
+        BigUint::from(base).pow(exponent)
+    }
+
+    #[test]
+    fn test_unfold_far() {
+        let line = ".??..??...?##. 1,1,3";
+        assert_eq!(count_per_line_with_unfolding(line, 10), 4u32 * pow(8, 9));
+        assert_eq!(count_per_line_with_unfolding(line, 20), 4u32 * pow(8, 19));
+
+        let line = "????.#...#... 4,1,1";
+        assert_eq!(count_per_line_with_unfolding(line, 20), pow(2, 19));
+
+        let line = "???.### 1,1,3";
+        assert_eq!(count_per_line_with_unfolding(line, 20), BigUint::one());
+    }
+
+    #[test]
+    fn test_unfold_beyond_u128() {
+        // every copy on its own already has 21 arrangements
+        let line = "???????? 1,1";
+        let result = count_per_line_with_unfolding(line, 100);
+        assert!(result > BigUint::from(u128::MAX));
+    }
+
+    #[test]
+    fn test_no_cache_collisions_beyond_255_springs() {
+        // 259 springs, which a key packed into 8 bits per length would confuse
+        let line = "?###???????? 3,2,1";
+        let result = count_per_line_with_unfolding(line, 20);
+        assert_eq!(result, 10u32 * pow(15, 19));
+        assert_eq!(result.to_string(), "221683782005310058593750");
     }
 }
@@ -0,0 +1,48 @@
+/// Fixed size bitset of any width, packed into `u64` words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bits {
+    words: Vec<u64>,
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+impl Bits {
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    pub fn set(&mut self, index: usize) {
+        self.words[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+    }
+
+    /// number of positions in which both bitsets differ
+    pub fn hamming_distance(&self, other: &Self) -> usize {
+        assert_eq!(self.words.len(), other.words.len());
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(word, other_word)| (word ^ other_word).count_ones() as usize)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hamming_distance() {
+        let mut a = Bits::zeros(200);
+        let mut b = Bits::zeros(200);
+        assert_eq!(a.hamming_distance(&b), 0);
+
+        a.set(3);
+        a.set(150);
+        b.set(150);
+        b.set(199);
+        assert_eq!(a.hamming_distance(&b), 2);
+        assert_eq!(b.hamming_distance(&a), 2);
+    }
+}
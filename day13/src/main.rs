@@ -1,6 +1,9 @@
+mod bits;
 mod part1;
 mod part2;
 
+use timings::Stage;
+
 pub fn read_input() -> String {
     use std::fs;
 
@@ -10,6 +13,24 @@ pub fn read_input() -> String {
 
 fn main() {
     let input = read_input();
+
+    // `day13 smudges <k>` summarizes the mirrors which need exactly k smudges to be fixed
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("smudges") {
+        let smudges = args[1].parse().unwrap();
+        let result = part2::run_with_smudges(&input, smudges);
+        println!("Result with {smudges} smudges is {result}");
+        return;
+    }
+
+    // `day13 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    let parse = || part2::parse(&input);
+    timings::run_from_args(&mut [
+        Stage::new("parse", parse),
+        Stage::with_setup("part1", parse, |maps| part2::summarize_all(&maps, 0)),
+        Stage::with_setup("part2", parse, |maps| part2::summarize_all(&maps, 1)),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
@@ -1,152 +1,162 @@
+use crate::bits::Bits;
+
 pub fn run(input: &str) -> usize {
-    input
-        .split("\n\n")
-        .map(Map::from_block)
-        .collect::<Vec<_>>()
-        .iter()
-        .map(|map| map.desmudge())
-        .map(|map| map.summarize())
-        .sum()
+    run_with_smudges(input, 1)
+}
+
+pub fn run_with_smudges(input: &str, smudges: usize) -> usize {
+    summarize_all(&parse(input), smudges)
+}
+
+pub fn parse(input: &str) -> Vec<Map> {
+    input.split("\n\n").map(Map::from_block).collect()
+}
+
+pub fn summarize_all(maps: &[Map], smudges: usize) -> usize {
+    maps.iter().map(|map| map.summarize(smudges)).sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mirror {
+    /// mirror below the row with this index
+    Row(usize),
+    /// mirror right of the column with this index
+    Col(usize),
+}
+
+impl Mirror {
+    fn summarize(&self) -> usize {
+        match self {
+            Self::Row(index) => 100 * (index + 1),
+            Self::Col(index) => index + 1,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Map {
-    row_hashes: Vec<u32>,
-    col_hashes: Vec<u32>,
-    ignored_row_mirror: Option<usize>,
-    ignored_col_mirror: Option<usize>,
+    rows: Vec<Bits>,
+    cols: Vec<Bits>,
 }
 
 impl Map {
     pub fn from_block(block: &str) -> Self {
-        fn parse_line(line: &str) -> u32 {
-            line.chars()
-                .enumerate()
-                .map(|(i, c)| parse_char(c) << i)
-                .sum()
-        }
-
-        fn parse_char(c: char) -> u32 {
+        fn parse_char(c: char) -> bool {
             match c {
-                '.' => 0,
-                '#' => 1,
+                '.' => false,
+                '#' => true,
                 _ => panic!("unknown map char"),
             }
         }
 
-        fn transpose_hashes(hashes: &[u32], len: usize) -> Vec<u32> {
-            let mut ret = vec![0; len];
-            for (i, hash) in hashes.iter().enumerate() {
-                for (j, val) in ret.iter_mut().enumerate() {
-                    *val += ((hash >> j) & 0b_1) << i;
-                }
+        let num_rows = block.lines().count();
+        let num_cols = block.lines().next().unwrap().chars().count();
+        let mut rows = vec![Bits::zeros(num_cols); num_rows];
+        let mut cols = vec![Bits::zeros(num_rows); num_cols];
+
+        for (row, line) in block.lines().enumerate() {
+            assert_eq!(line.chars().count(), num_cols, "map is not rectangular");
+            for (col, _) in line.chars().enumerate().filter(|(_, c)| parse_char(*c)) {
+                rows[row].set(col);
+                cols[col].set(row);
             }
-            ret
         }
 
-        let row_hashes: Vec<_> = block.lines().map(parse_line).collect();
-        let col_len = block.lines().nth(0).unwrap().chars().count();
-        let col_hashes = transpose_hashes(&row_hashes, col_len);
+        Self { rows, cols }
+    }
+
+    /// Number of cells that differ from their mirror image, when mirroring after `index`. Stops
+    /// counting as soon as `limit` is exceeded.
+    fn reflection_distance(lines: &[Bits], index: usize, limit: usize) -> usize {
+        let first_side = lines.iter().take(index + 1).rev();
+        let second_side = lines.iter().skip(index + 1);
 
-        Self {
-            row_hashes,
-            col_hashes,
-            ignored_row_mirror: None,
-            ignored_col_mirror: None,
+        let mut distance = 0;
+        for (first, second) in first_side.zip(second_side) {
+            distance += first.hamming_distance(second);
+            if distance > limit {
+                break;
+            }
         }
+        distance
     }
 
-    fn flip(&mut self, row: usize, col: usize) {
-        let row_hash = self.row_hashes.get_mut(row).unwrap();
-        let col_hash = self.col_hashes.get_mut(col).unwrap();
-        let row_mask = 1 << col;
-        let col_mask = 1 << row;
-        *row_hash ^= row_mask;
-        *col_hash ^= col_mask;
+    fn mirrors_in(lines: &[Bits], smudges: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..lines.len() - 1)
+            .filter(move |index| Self::reflection_distance(lines, *index, smudges) == smudges)
     }
 
-    fn flipped_iter(&self) -> impl Iterator<Item = Self> + '_ {
-        let rows = 0..self.row_hashes.len();
-        let cols = 0..self.col_hashes.len();
-        let row_col_combinations =
-            rows.flat_map(move |row| cols.clone().map(move |col| (row, col)));
-        row_col_combinations.map(|(row, col)| {
-            let mut clone = self.clone();
-            clone.flip(row, col);
-            clone
-        })
+    /// All mirrors which need exactly `smudges` cells to be fixed, rows first.
+    pub fn mirrors(&self, smudges: usize) -> Vec<Mirror> {
+        let rows = Self::mirrors_in(&self.rows, smudges).map(Mirror::Row);
+        let cols = Self::mirrors_in(&self.cols, smudges).map(Mirror::Col);
+        rows.chain(cols).collect()
     }
 
-    fn desmudge(&self) -> Self {
-        let orig_row_index = Self::mirroring_iter(&self.row_hashes).next();
-        let orig_col_index = Self::mirroring_iter(&self.col_hashes).next();
-        let mut map = self
-            .flipped_iter()
-            .find(|map| {
-                let row = Self::find_new_mirror_index(&map.row_hashes, orig_row_index);
-                let col = Self::find_new_mirror_index(&map.col_hashes, orig_col_index);
-                row.xor(col).is_some()
-            })
-            .unwrap();
-        map.ignored_row_mirror = orig_row_index;
-        map.ignored_col_mirror = orig_col_index;
-        map
+    pub fn summarize(&self, smudges: usize) -> usize {
+        self.mirrors(smudges).iter().map(Mirror::summarize).sum()
     }
+}
 
-    fn is_mirroring(hashes: &[u32], index: usize) -> bool {
-        let first_side = hashes.iter().take(index + 1).rev();
-        let second_side = hashes.iter().skip(index + 1);
-        first_side
-            .zip(second_side)
-            .all(|(first, second)| first == second)
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn mirroring_iter(hashes: &[u32]) -> impl Iterator<Item = usize> + '_ {
-        hashes
-            .iter()
-            .take(hashes.len() - 1)
-            .enumerate()
-            .map(|(i, _)| i)
-            .filter(|i| Self::is_mirroring(hashes, *i))
-    }
+    const EXAMPLE: &str = "\
+        #.##..##.\n\
+        ..#.##.#.\n\
+        ##......#\n\
+        ##......#\n\
+        ..#.##.#.\n\
+        ..##..##.\n\
+        #.#.##.#.\n\
+        \n\
+        #...##..#\n\
+        #....#..#\n\
+        ..##..###\n\
+        #####.##.\n\
+        #####.##.\n\
+        ..##..###\n\
+        #....#..#";
 
-    fn find_new_mirror_index(hashes: &[u32], orig_index: Option<usize>) -> Option<usize> {
-        Self::mirroring_iter(hashes).find(|&index| orig_index != Some(index))
+    #[test]
+    fn test_example() {
+        assert_eq!(run(EXAMPLE), 400);
     }
 
-    pub fn summarize(&self) -> usize {
-        let row = Self::find_new_mirror_index(&self.row_hashes, self.ignored_row_mirror)
-            .map(|x| x + 1)
-            .unwrap_or(0);
-        let col = Self::find_new_mirror_index(&self.col_hashes, self.ignored_col_mirror)
-            .map(|x| x + 1)
-            .unwrap_or(0);
+    #[test]
+    fn test_no_smudges_is_part1() {
+        assert_eq!(run_with_smudges(EXAMPLE, 0), crate::part1::run(EXAMPLE));
+    }
 
-        100 * row + col
+    #[test]
+    fn test_mirrors() {
+        let map = Map::from_block(EXAMPLE.split("\n\n").next().unwrap());
+        assert_eq!(map.mirrors(0), [Mirror::Col(4)]);
+        assert_eq!(map.mirrors(1), [Mirror::Row(2)]);
     }
-}
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_example() {
-        let input = "\
-            #.##..##.\n\
-            ..#.##.#.\n\
-            ##......#\n\
-            ##......#\n\
-            ..#.##.#.\n\
-            ..##..##.\n\
-            #.#.##.#.\n\
-            \n\
-            #...##..#\n\
-            #....#..#\n\
-            ..##..###\n\
-            #####.##.\n\
-            #####.##.\n\
-            ..##..###\n\
-            #....#..#";
-        assert_eq!(run(input), 400);
+    fn test_wide_map() {
+        // 100 columns mirrored right of column 69, with 2 smudges in the last row
+        let row = |pattern: fn(usize) -> bool| {
+            let left: String = (0..70)
+                .map(|i| if pattern(i) { '#' } else { '.' })
+                .collect();
+            let mirrored: String = left.chars().rev().take(30).collect();
+            format!("{left}{mirrored}")
+        };
+        let first = row(|i| (i * i + 3 * i) % 7 < 3);
+        let second = row(|i| (i * i * i + i) % 11 < 5);
+        let mut smudged = first.clone().into_bytes();
+        smudged[75] ^= b'#' ^ b'.';
+        smudged[76] ^= b'#' ^ b'.';
+        let smudged = String::from_utf8(smudged).unwrap();
+        let block = [first, second, smudged].join("\n");
+
+        let map = Map::from_block(&block);
+        assert!(!map.mirrors(0).contains(&Mirror::Col(69)));
+        assert!(!map.mirrors(1).contains(&Mirror::Col(69)));
+        assert!(map.mirrors(2).contains(&Mirror::Col(69)));
     }
 }
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A sequence of states, which runs in a loop of `lambda` steps after the first `mu` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cycle of length {} starting at iteration {}",
+            self.lambda, self.mu
+        )
+    }
+}
+
+impl Cycle {
+    /// Steps `state` forward `num_steps` times, but takes a shortcut as soon as a state repeats.
+    /// Equal keys are taken as equal states, so `key` has to identify a state exactly.
+    pub fn advance<S, K: Hash + Eq>(
+        state: &mut S,
+        num_steps: usize,
+        mut step: impl FnMut(&mut S),
+        key: impl Fn(&S) -> K,
+    ) -> Option<Self> {
+        let mut seen = HashMap::new();
+
+        for num_done in 0..num_steps {
+            if let Some(mu) = seen.insert(key(state), num_done) {
+                let cycle = Self {
+                    mu,
+                    lambda: num_done - mu,
+                };
+
+                // every full loop ends up at the current state again
+                let num_missing = (num_steps - num_done) % cycle.lambda;
+                for _ in 0..num_missing {
+                    step(state);
+                }
+                return Some(cycle);
+            }
+            step(state);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, 2, 3, 4, 5, 6, 3, 4, 5, 6, 3, ...
+    fn step(state: &mut usize) {
+        *state = if *state < 6 { *state + 1 } else { 3 };
+    }
+
+    #[test]
+    fn test_detect() {
+        let mut state = 0;
+        let cycle = Cycle::advance(&mut state, 100, step, |state| *state);
+        assert_eq!(cycle, Some(Cycle { mu: 3, lambda: 4 }));
+    }
+
+    #[test]
+    fn test_advance_matches_stepping() {
+        for num_steps in 0..30 {
+            let mut expected = 0;
+            for _ in 0..num_steps {
+                step(&mut expected);
+            }
+
+            let mut state = 0;
+            Cycle::advance(&mut state, num_steps, step, |state| *state);
+            assert_eq!(state, expected, "after {num_steps} steps");
+        }
+    }
+
+    #[test]
+    fn test_no_cycle_within_steps() {
+        let mut state = 0;
+        let cycle = Cycle::advance(&mut state, 5, step, |state| *state);
+        assert_eq!(cycle, None);
+        assert_eq!(state, 5);
+    }
+}
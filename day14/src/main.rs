@@ -1,5 +1,10 @@
+mod cycle;
 mod part1;
 mod part2;
+mod playback;
+mod program;
+
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -10,10 +15,57 @@ pub fn read_input() -> String {
 
 fn main() {
     let input = read_input();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let program = |index: usize| -> program::Program { args[index].parse().unwrap() };
+
+    // `day14 run <program>` runs a tilt program like `"NWSE"*1000000000` with cycle shortcuts
+    if args.first().map(String::as_str) == Some("run") {
+        let mut panel: part2::Panel = input.parse().unwrap();
+        for cycle in program(1).run(&mut panel).into_iter().flatten() {
+            println!("{cycle} was used as shortcut");
+        }
+        println!("Load is {}", panel.calc_load());
+        return;
+    }
+
+    // `day14 play <program> [steps] [delay in ms]` shows each tilt of a program
+    if args.first().map(String::as_str) == Some("play") {
+        let mut panel: part2::Panel = input.parse().unwrap();
+        let steps = args.get(2).map_or(100, |steps| steps.parse().unwrap());
+        let delay = args.get(3).map_or(200, |delay| delay.parse().unwrap());
+        let delay = std::time::Duration::from_millis(delay);
+        let mut stdout = std::io::stdout();
+        playback::play(&mut panel, &program(1), steps, delay, &mut stdout).unwrap();
+        return;
+    }
+
+    // `day14 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || {
+            let panel1: part1::Panel = input.parse().unwrap();
+            let panel2: part2::Panel = input.parse().unwrap();
+            (panel1, panel2)
+        }),
+        Stage::with_setup(
+            "part1",
+            || input.parse::<part1::Panel>().unwrap(),
+            |panel| panel.calc_tilted_load(),
+        ),
+        Stage::with_setup(
+            "part2",
+            || input.parse::<part2::Panel>().unwrap(),
+            part2::spin_and_weigh,
+        ),
+    ]);
+
     let result1 = part1::run(&input);
-    let result2 = part2::run(&input);
+    let (result2, cycle) = part2::run_with_cycle(&input);
     println!("Result1 is {result1}");
     println!("Result2 is {result2}");
+    if let Some(cycle) = cycle {
+        println!("{cycle} was used as shortcut");
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,21 @@
+use crate::cycle::Cycle;
+use crate::program::Tilt;
+
 use std::{collections::VecDeque, str::FromStr};
 
+#[allow(dead_code)] // main also reports the cycle
 pub fn run(input: &str) -> usize {
-    let mut panel = Panel::from_str(input).unwrap();
-    panel.spin(1000000000);
-    panel.calc_load()
+    run_with_cycle(input).0
+}
+
+/// also returns the cycle which was used as shortcut, if any
+pub fn run_with_cycle(input: &str) -> (usize, Option<Cycle>) {
+    spin_and_weigh(Panel::from_str(input).unwrap())
+}
+
+pub fn spin_and_weigh(mut panel: Panel) -> (usize, Option<Cycle>) {
+    let cycle = panel.spin(1000000000);
+    (panel.calc_load(), cycle)
 }
 
 #[derive(Clone, PartialEq)]
@@ -59,64 +71,53 @@ impl Panel {
         weights.into_iter().sum()
     }
 
-    pub fn spin(&mut self, cycles: usize) {
-        if cycles == 0 {
-            return;
-        }
-
-        let mut old_hashes = Vec::new();
-
-        let cycle = 'find_cycle_length: {
-            for _ in 0..cycles {
-                self.spin_once();
+    pub fn spin(&mut self, cycles: usize) -> Option<Cycle> {
+        Cycle::advance(self, cycles, Self::spin_once, Self::round_rocks)
+    }
 
-                let new_hash = self.hash();
-                if let Some(hash_index) = old_hashes.iter().position(|&x| x == new_hash) {
-                    let cycle_start = hash_index;
-                    let cycle_length = old_hashes.len() - hash_index;
-                    break 'find_cycle_length Some((cycle_start, cycle_length));
-                }
-                old_hashes.push(new_hash);
+    /// Bitmap of all round rocks, packed into words. Squares never change, so this identifies
+    /// the state exactly.
+    fn round_rocks(&self) -> Vec<u64> {
+        let positions = self.positions.iter().flatten();
+        let mut words = Vec::new();
+        for (index, pos) in positions.enumerate() {
+            if index % 64 == 0 {
+                words.push(0);
             }
-            None
-        };
-
-        let Some((cycle_start, cycle_length)) = cycle else {
-            return;
-        };
+            if pos == &Position::Round {
+                *words.last_mut().unwrap() |= 1 << (index % 64);
+            }
+        }
+        words
+    }
 
-        println!("cycle of length {cycle_length} starting at iteration {cycle_start} detected, taking shortcut");
+    /// runs all `tilts` in a row, and that `repeat` times
+    pub fn run_tilts(&mut self, tilts: &[Tilt], repeat: usize) -> Option<Cycle> {
+        let run_once = |panel: &mut Self| panel.tilt_all(tilts);
+        Cycle::advance(self, repeat, run_once, Self::round_rocks)
+    }
 
-        // cycle was detected, maybe some spins are still missing
-        assert_ne!(cycle_length, 0);
-        let missing_cycles = (cycles - cycle_start - 1) % cycle_length;
+    fn spin_once(&mut self) {
+        self.tilt_all(&Tilt::SPIN);
+    }
 
-        for _ in 0..missing_cycles {
-            self.spin_once();
+    fn tilt_all(&mut self, tilts: &[Tilt]) {
+        for tilt in tilts {
+            self.tilt(*tilt);
         }
     }
 
-    fn hash(&self) -> usize {
-        let row_len = self.positions[0].len();
-        let mut hash = 0;
-        for (row, row_data) in self.positions.iter().enumerate() {
-            for (col, pos) in row_data.iter().enumerate() {
-                match pos {
-                    Position::Empty => (), // empty will be counted as 0
-                    Position::Round => hash += row * row_len + col,
-                    Position::Square => (), // squares never change, so don't add them to the hash
-                }
-            }
+    pub fn tilt(&mut self, tilt: Tilt) {
+        match tilt {
+            Tilt::North => self.tilt_north(),
+            Tilt::West => self.tilt_west(),
+            Tilt::South => self.tilt_south(),
+            Tilt::East => self.tilt_east(),
         }
-
-        hash
     }
 
-    fn spin_once(&mut self) {
-        self.tilt_north();
-        self.tilt_west();
-        self.tilt_south();
-        self.tilt_east();
+    pub fn rows(&self) -> &[Vec<Position>] {
+        &self.positions
     }
 
     fn tilt_north(&mut self) {
@@ -502,4 +503,30 @@ mod tests {
             #OO..#....";
         assert_eq!(run(input), 64);
     }
+
+    #[test]
+    fn test_example_cycle() {
+        let input = "\
+            O....#....\n\
+            O.OO#....#\n\
+            .....##...\n\
+            OO.#O....O\n\
+            .O.....O#.\n\
+            O.#..O.#.#\n\
+            ..O..#O..O\n\
+            .......O..\n\
+            #....###..\n\
+            #OO..#....";
+        let (load, cycle) = run_with_cycle(input);
+        assert_eq!(load, 64);
+        assert_eq!(cycle, Some(Cycle { mu: 3, lambda: 7 }));
+    }
+
+    #[test]
+    fn test_round_rocks_are_exact() {
+        // both would sum up to the same `row * row_len + col`
+        let a = Panel::from_str("O..\n..O").unwrap();
+        let b = Panel::from_str(".O.\n.O.").unwrap();
+        assert_ne!(a.round_rocks(), b.round_rocks());
+    }
 }
@@ -0,0 +1,87 @@
+use crate::part2::{Panel, Position};
+use crate::program::Program;
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const ROUND_COLOR: &str = "\x1b[33m";
+
+/// round rocks in bold yellow, square rocks dimmed
+pub fn ansi(panel: &Panel) -> String {
+    let mut out = String::new();
+    for row in panel.rows() {
+        for position in row {
+            let symbol = match position {
+                Position::Empty => ".".to_string(),
+                Position::Round => format!("{BOLD}{ROUND_COLOR}O{RESET}"),
+                Position::Square => format!("{DIM}#{RESET}"),
+            };
+            out.push_str(&symbol);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Shows the panel after each of the first `max_steps` tilts of the program, one frame every
+/// `delay`. This runs every single tilt, so there are no shortcuts for long programs.
+pub fn play(
+    panel: &mut Panel,
+    program: &Program,
+    max_steps: usize,
+    delay: Duration,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    write!(
+        out,
+        "{CLEAR}step 0: start, load {}\n{}",
+        panel.calc_load(),
+        ansi(panel)
+    )?;
+    out.flush()?;
+
+    for (step, tilt) in program.tilts().take(max_steps).enumerate() {
+        std::thread::sleep(delay);
+        panel.tilt(tilt);
+        write!(
+            out,
+            "{CLEAR}step {}: tilt {tilt:?}, load {}\n{}",
+            step + 1,
+            panel.calc_load(),
+            ansi(panel)
+        )?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi() {
+        let panel: Panel = ".O#".parse().unwrap();
+        assert_eq!(
+            ansi(&panel),
+            format!(".{BOLD}{ROUND_COLOR}O{RESET}{DIM}#{RESET}\n")
+        );
+    }
+
+    #[test]
+    fn test_play() {
+        let mut panel: Panel = "O.\n.O".parse().unwrap();
+        let program: Program = "\"NWSE\"*1000000000".parse().unwrap();
+        let mut out = Vec::new();
+        play(&mut panel, &program, 6, Duration::ZERO, &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches(CLEAR).count(), 7);
+        assert!(out.contains("step 5: tilt North, load 4"));
+        assert!(out.ends_with(&ansi(&panel)));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::cycle::Cycle;
+use crate::part2::Panel;
+
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tilt {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl Tilt {
+    /// one spin cycle of the puzzle
+    pub const SPIN: [Self; 4] = [Self::North, Self::West, Self::South, Self::East];
+}
+
+impl TryFrom<char> for Tilt {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'N' => Ok(Self::North),
+            'W' => Ok(Self::West),
+            'S' => Ok(Self::South),
+            'E' => Ok(Self::East),
+            _ => Err(format!("unknown tilt '{value}'")),
+        }
+    }
+}
+
+/// tilts which are run in a row, and that `repeat` times
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    tilts: Vec<Tilt>,
+    repeat: usize,
+}
+
+impl FromStr for Term {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (tilts, repeat) = match s.split_once('*') {
+            Some((tilts, repeat)) => {
+                let repeat = repeat
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid repetition '{repeat}'"))?;
+                (tilts, repeat)
+            }
+            None => (s, 1),
+        };
+
+        let tilts = tilts.trim().trim_matches('"');
+        if tilts.is_empty() {
+            return Err(format!("no tilts in '{s}'"));
+        }
+        let tilts = tilts
+            .chars()
+            .map(Tilt::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { tilts, repeat })
+    }
+}
+
+/// Sequence of tilts, written like `"NWSE"*1000000000` or `"NN"*3 "EW"`. Every term is run
+/// through the cycle detection on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    terms: Vec<Term>,
+}
+
+impl FromStr for Program {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms: Vec<Term> = s
+            .split_whitespace()
+            .map(Term::from_str)
+            .collect::<Result<_, _>>()?;
+        if terms.is_empty() {
+            return Err("empty program".to_string());
+        }
+        Ok(Self { terms })
+    }
+}
+
+impl Program {
+    /// returns the cycle that was used as shortcut for each term
+    pub fn run(&self, panel: &mut Panel) -> Vec<Option<Cycle>> {
+        self.terms
+            .iter()
+            .map(|term| panel.run_tilts(&term.tilts, term.repeat))
+            .collect()
+    }
+
+    /// every single tilt of the program, without any shortcuts
+    pub fn tilts(&self) -> impl Iterator<Item = Tilt> + '_ {
+        self.terms.iter().flat_map(|term| {
+            std::iter::repeat_n(&term.tilts, term.repeat)
+                .flatten()
+                .copied()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        O....#....\n\
+        O.OO#....#\n\
+        .....##...\n\
+        OO.#O....O\n\
+        .O.....O#.\n\
+        O.#..O.#.#\n\
+        ..O..#O..O\n\
+        .......O..\n\
+        #....###..\n\
+        #OO..#....";
+
+    #[test]
+    fn test_parse() {
+        let program: Program = "\"NWSE\"*1000000000".parse().unwrap();
+        let expected = Term {
+            tilts: Tilt::SPIN.to_vec(),
+            repeat: 1000000000,
+        };
+        assert_eq!(program.terms, [expected]);
+
+        let program: Program = "NNEW \"S\"*2".parse().unwrap();
+        let tilts: Vec<Tilt> = program.tilts().collect();
+        use Tilt::*;
+        assert_eq!(tilts, [North, North, East, West, South, South]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<Program>().is_err());
+        assert!("\"NX\"".parse::<Program>().is_err());
+        assert!("\"N\"*x".parse::<Program>().is_err());
+        assert!("\"\"*2".parse::<Program>().is_err());
+    }
+
+    #[test]
+    fn test_puzzle_program() {
+        let mut panel: Panel = EXAMPLE.parse().unwrap();
+        let program: Program = "\"NWSE\"*1000000000".parse().unwrap();
+        let cycles = program.run(&mut panel);
+        assert_eq!(panel.calc_load(), 64);
+        assert!(cycles[0].is_some());
+    }
+
+    #[test]
+    fn test_program_matches_single_tilts() {
+        let program: Program = "\"NNEW\"*5 SE".parse().unwrap();
+
+        let mut panel: Panel = EXAMPLE.parse().unwrap();
+        program.run(&mut panel);
+
+        let mut expected: Panel = EXAMPLE.parse().unwrap();
+        for tilt in program.tilts() {
+            expected.tilt(tilt);
+        }
+        assert_eq!(panel, expected);
+    }
+}
@@ -1,5 +1,9 @@
 mod part1;
 mod part2;
+mod table;
+mod trace;
+
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -10,6 +14,48 @@ pub fn read_input() -> String {
 
 fn main() {
     let input = read_input();
+
+    // `day15 table <hash|fnv|len> [boxes] [label...]` runs part 2 with another hash table
+    // layout, and looks up the given labels afterwards
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("table") {
+        let hash_fn: fn(&&str) -> usize = match args[1].as_str() {
+            "hash" => |label| part1::hash(label),
+            "fnv" => |label| table::fnv1a(label),
+            "len" => |label| label.len(),
+            other => panic!("unknown hash function '{other}'"),
+        };
+        let num_boxes = args.get(2).map_or(256, |boxes| boxes.parse().unwrap());
+        let mut boxes = table::Table::new(num_boxes, hash_fn);
+        part2::execute_all(&input, &mut boxes);
+        println!("{}", boxes.stats());
+        println!("Focusing power is {}", part2::focusing_power(&boxes));
+        for label in args.iter().skip(3) {
+            let box_id = boxes.bucket_index(&label.as_str());
+            match boxes.get(&label.as_str()) {
+                Some(lens) => println!("{label}: {lens:?} in box {box_id}"),
+                None => println!("{label}: no lens in box {box_id}"),
+            }
+        }
+        return;
+    }
+
+    // `day15 trace [steps]` shows the boxes after each of the first steps
+    if args.first().map(String::as_str) == Some("trace") {
+        let steps = args
+            .get(1)
+            .map_or(usize::MAX, |steps| steps.parse().unwrap());
+        print!("{}", trace::walkthrough(trace::trace(&input).take(steps)));
+        return;
+    }
+
+    // `day15 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || part2::parse(&input)),
+        Stage::new("part1 (incl. parse)", || part1::run(&input)),
+        Stage::with_setup("part2", || part2::parse(&input), part2::arrange_lenses),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
@@ -1,82 +1,64 @@
 use crate::part1::hash;
-
-use linked_hash_map::LinkedHashMap;
+use crate::table::Table;
 
 pub fn run(input: &str) -> usize {
-    let mut boxes = vec![LensBox::default(); 256];
-    input
-        .split(',')
-        .map(Instruction::parse)
-        .for_each(|instruction| instruction.execute(&mut boxes));
-    boxes
-        .into_iter()
-        .enumerate()
-        .map(|(box_id, lens_box)| lens_box.focusing_power(box_id))
-        .sum()
+    arrange_lenses(parse(input))
 }
 
-#[derive(Debug, Default, Clone)]
-struct LensBox<'a> {
-    lenses: LinkedHashMap<&'a str, Lens>,
+pub fn parse(input: &str) -> Vec<Instruction<'_>> {
+    input.split(',').map(Instruction::parse).collect()
 }
 
-impl<'a> LensBox<'a> {
-    fn remove_lens(&mut self, label: &str) {
-        self.lenses.remove(label);
+pub fn arrange_lenses(instructions: Vec<Instruction>) -> usize {
+    let mut boxes = Table::new(256, |label: &&str| hash(label));
+    for instruction in instructions {
+        instruction.execute(&mut boxes);
     }
+    focusing_power(&boxes)
+}
 
-    fn add_lens(&mut self, label: &'a str, focal_length: usize) {
-        let lens = Lens::new(focal_length);
-        if let Some(existing_lens) = self.lenses.get_mut(label) {
-            existing_lens.replace(lens);
-            return;
-        }
-        let existing = self.lenses.insert(label, lens);
-        assert!(existing.is_none());
-    }
+pub fn execute_all<'a, F: Fn(&&'a str) -> usize>(
+    input: &'a str,
+    boxes: &mut Table<&'a str, Lens, F>,
+) {
+    input
+        .split(',')
+        .map(Instruction::parse)
+        .for_each(|instruction| instruction.execute(boxes));
+}
 
-    fn focusing_power(self, box_id: usize) -> usize {
-        let box_mult = box_id + 1;
-        let lens_mult: usize = self
-            .lenses
-            .into_iter()
-            .enumerate()
-            .map(|(i, (_label, lens))| lens.focal_length * (i + 1))
-            .sum();
-        lens_mult * box_mult
-    }
+/// works for any number of boxes and any hash function
+pub fn focusing_power<F>(boxes: &Table<&str, Lens, F>) -> usize {
+    boxes
+        .iter()
+        .map(|(box_id, slot, _label, lens)| (box_id + 1) * (slot + 1) * lens.focal_length)
+        .sum()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Lens {
+pub struct Lens {
     focal_length: usize,
 }
 impl Lens {
     fn new(focal_length: usize) -> Self {
         Self { focal_length }
     }
-    fn replace(&mut self, other: Self) {
-        self.focal_length = other.focal_length;
+    pub fn focal_length(&self) -> usize {
+        self.focal_length
     }
 }
 
-struct Instruction<'a> {
-    box_id: usize,
+pub struct Instruction<'a> {
     label: &'a str,
     operation: Operation,
 }
 
 impl<'a> Instruction<'a> {
-    fn parse(s: &'a str) -> Self {
+    pub fn parse(s: &'a str) -> Self {
         let s = Self::remove_newline(s);
         let operation = Operation::parse(s);
         let label = Self::parse_label(s, operation);
-        let box_id = hash(label);
-        Self {
-            box_id,
-            label,
-            operation,
-        }
+        Self { label, operation }
     }
 
     fn remove_newline(s: &str) -> &str {
@@ -98,11 +80,23 @@ impl<'a> Instruction<'a> {
         &s[..label_len]
     }
 
-    fn execute(self, boxes: &mut [LensBox<'a>]) {
-        let target_box = &mut boxes[self.box_id];
+    pub fn execute<F: Fn(&&'a str) -> usize>(self, boxes: &mut Table<&'a str, Lens, F>) {
         match self.operation {
-            Operation::RemoveLens => target_box.remove_lens(self.label),
-            Operation::NewLens { focal_length } => target_box.add_lens(self.label, focal_length),
+            Operation::RemoveLens => {
+                boxes.remove(&self.label);
+            }
+            Operation::NewLens { focal_length } => {
+                boxes.insert(self.label, Lens::new(focal_length));
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operation {
+            Operation::RemoveLens => write!(f, "{}-", self.label),
+            Operation::NewLens { focal_length } => write!(f, "{}={focal_length}", self.label),
         }
     }
 }
@@ -130,90 +124,98 @@ impl Operation {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn show_lenses(boxes: &[LensBox]) -> String {
-        let mut ret = String::new();
-        let mut is_first = true;
-        'boxloop: for (box_id, lens_box) in boxes.iter().enumerate() {
-            if lens_box.lenses.is_empty() {
-                continue 'boxloop;
-            }
-            if is_first {
-                is_first = false;
-            } else {
-                ret.push('\n');
-            }
-            ret.push_str(&format!("Box {box_id}:"));
-            for (label, lens) in &lens_box.lenses {
-                let focal_length = lens.focal_length;
-                ret.push_str(&format!(" [{label} {focal_length}]"));
-            }
-        }
-        ret
-    }
+    use crate::trace::show_boxes;
 
     #[test]
     fn test_example() {
-        let mut boxes = vec![LensBox::default(); 256];
+        let mut boxes = Table::new(256, |label: &&str| hash(label));
 
         Instruction::parse("rn=1").execute(&mut boxes);
         let expectation = "Box 0: [rn 1]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("cm-").execute(&mut boxes);
         let expectation = "Box 0: [rn 1]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("qp=3").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1]\n\
             Box 1: [qp 3]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("cm=2").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1] [cm 2]\n\
             Box 1: [qp 3]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("qp-").execute(&mut boxes);
         let expectation = "Box 0: [rn 1] [cm 2]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("pc=4").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1] [cm 2]\n\
             Box 3: [pc 4]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("ot=9").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1] [cm 2]\n\
             Box 3: [pc 4] [ot 9]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("ab=5").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1] [cm 2]\n\
             Box 3: [pc 4] [ot 9] [ab 5]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("pc-").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1] [cm 2]\n\
             Box 3: [ot 9] [ab 5]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("pc=6").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1] [cm 2]\n\
             Box 3: [ot 9] [ab 5] [pc 6]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
 
         Instruction::parse("ot=7").execute(&mut boxes);
         let expectation = "\
             Box 0: [rn 1] [cm 2]\n\
             Box 3: [ot 7] [ab 5] [pc 6]";
-        assert_eq!(show_lenses(&boxes), expectation);
+        assert_eq!(show_boxes(&boxes), expectation);
+    }
+
+    #[test]
+    fn test_instruction_display() {
+        for s in ["rn=1", "cm-", "qp=3\n"] {
+            let instruction = Instruction::parse(s);
+            assert_eq!(instruction.to_string(), s.trim_end());
+        }
+    }
+
+    #[test]
+    fn test_focusing_power_with_other_tables() {
+        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+        assert_eq!(run(input), 145);
+
+        // a single box: rn 1, cm 2, ot 7, ab 5, pc 6
+        let mut boxes = Table::new(1, |_: &&str| 0);
+        execute_all(input, &mut boxes);
+        assert_eq!(focusing_power(&boxes), 1 + 2 * 2 + 7 * 3 + 5 * 4 + 6 * 5);
+
+        // every label has a length of 2, so they all end up in box 2
+        let mut boxes = Table::new(3, |label: &&str| label.len());
+        execute_all(input, &mut boxes);
+        assert_eq!(
+            focusing_power(&boxes),
+            3 * (1 + 2 * 2 + 7 * 3 + 5 * 4 + 6 * 5)
+        );
+        assert_eq!(boxes.stats().collisions(), 4);
     }
 }
@@ -0,0 +1,172 @@
+use linked_hash_map::LinkedHashMap;
+
+use std::hash::Hash;
+
+/// 64 bit FNV-1a hash, as an alternative to the puzzle's HASH
+pub fn fnv1a(s: &str) -> usize {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let hash = s.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    hash as usize
+}
+
+/// Hash table with a fixed number of buckets, each keeping its entries in insertion order.
+/// Keys go into the bucket `hash_fn(key) % num_buckets`.
+#[derive(Debug, Clone)]
+pub struct Table<K: Hash + Eq, V, F> {
+    buckets: Vec<LinkedHashMap<K, V>>,
+    hash_fn: F,
+}
+
+impl<K: Hash + Eq, V, F> Table<K, V, F> {
+    pub fn num_buckets(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.iter().map(LinkedHashMap::len).sum()
+    }
+
+    /// all entries as `(bucket index, index in bucket, key, value)`, bucket by bucket
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &K, &V)> {
+        self.buckets.iter().enumerate().flat_map(|(index, bucket)| {
+            bucket
+                .iter()
+                .enumerate()
+                .map(move |(slot, (key, value))| (index, slot, key, value))
+        })
+    }
+
+    pub fn stats(&self) -> Stats {
+        let bucket_lens = self.buckets.iter().map(LinkedHashMap::len);
+        Stats {
+            num_entries: self.len(),
+            num_buckets: self.num_buckets(),
+            used_buckets: bucket_lens.clone().filter(|len| *len > 0).count(),
+            longest_bucket: bucket_lens.max().unwrap_or(0),
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, F: Fn(&K) -> usize> Table<K, V, F> {
+    pub fn new(num_buckets: usize, hash_fn: F) -> Self {
+        assert_ne!(num_buckets, 0, "table needs at least one bucket");
+        let buckets = std::iter::repeat_with(LinkedHashMap::new)
+            .take(num_buckets)
+            .collect();
+        Self { buckets, hash_fn }
+    }
+
+    pub fn bucket_index(&self, key: &K) -> usize {
+        (self.hash_fn)(key) % self.num_buckets()
+    }
+
+    /// Replacing the value of an existing key keeps its place in the bucket.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let index = self.bucket_index(&key);
+        let bucket = &mut self.buckets[index];
+        if let Some(existing) = bucket.get_mut(&key) {
+            return Some(std::mem::replace(existing, value));
+        }
+        bucket.insert(key, value);
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.bucket_index(key);
+        self.buckets[index].remove(key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.buckets[self.bucket_index(key)].get(key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub num_entries: usize,
+    pub num_buckets: usize,
+    pub used_buckets: usize,
+    pub longest_bucket: usize,
+}
+
+impl Stats {
+    pub fn load_factor(&self) -> f64 {
+        self.num_entries as f64 / self.num_buckets as f64
+    }
+
+    /// entries that share their bucket with an earlier one
+    pub fn collisions(&self) -> usize {
+        self.num_entries - self.used_buckets
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} entries in {} of {} buckets, load factor {:.3}, {} collisions, longest bucket {}",
+            self.num_entries,
+            self.used_buckets,
+            self.num_buckets,
+            self.load_factor(),
+            self.collisions(),
+            self.longest_bucket
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_insert_keeps_order() {
+        let mut table = Table::new(1, |_: &&str| 0);
+        assert_eq!(table.insert("a", 1), None);
+        assert_eq!(table.insert("b", 2), None);
+        assert_eq!(table.insert("a", 3), Some(1));
+
+        let entries: Vec<_> = table.iter().collect();
+        assert_eq!(entries, [(0, 0, &"a", &3), (0, 1, &"b", &2)]);
+    }
+
+    #[test]
+    fn test_remove_and_get() {
+        let mut table = Table::new(4, |key: &&str| key.len());
+        table.insert("a", 1);
+        table.insert("bb", 2);
+        assert_eq!(table.get(&"bb"), Some(&2));
+        assert_eq!(table.remove(&"bb"), Some(2));
+        assert_eq!(table.remove(&"bb"), None);
+        assert_eq!(table.get(&"bb"), None);
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_iter_and_stats() {
+        let mut table = Table::new(3, |key: &usize| *key);
+        for key in [0, 3, 6, 1, 4, 2] {
+            table.insert(key, key * 10);
+        }
+        let keys: Vec<_> = table
+            .iter()
+            .map(|(index, _, key, _)| (index, *key))
+            .collect();
+        assert_eq!(keys, [(0, 0), (0, 3), (0, 6), (1, 1), (1, 4), (2, 2)]);
+
+        let stats = table.stats();
+        assert_eq!(stats.load_factor(), 2.0);
+        assert_eq!(stats.collisions(), 3);
+        assert_eq!(stats.longest_bucket, 3);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::part1::hash;
+use crate::part2::{Instruction, Lens};
+use crate::table::Table;
+
+/// the boxes as in the puzzle, so the trace matches its walkthrough
+type Boxes<'a> = Table<&'a str, Lens, fn(&&str) -> usize>;
+
+/// Executes the instructions one by one and yields the state after each of them. Use `take`
+/// to stop after a chosen step.
+pub struct Trace<'a> {
+    instructions: std::str::Split<'a, char>,
+    boxes: Boxes<'a>,
+}
+
+pub fn trace(input: &str) -> Trace<'_> {
+    let hash_fn: fn(&&str) -> usize = |label| hash(label);
+    Trace {
+        instructions: input.split(','),
+        boxes: Table::new(256, hash_fn),
+    }
+}
+
+impl Iterator for Trace<'_> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let instruction = Instruction::parse(self.instructions.next()?);
+        let step = instruction.to_string();
+        instruction.execute(&mut self.boxes);
+        Some(Step {
+            instruction: step,
+            boxes: show_boxes(&self.boxes),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub instruction: String,
+    /// one line per non-empty box
+    pub boxes: String,
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "After \"{}\":", self.instruction)?;
+        if !self.boxes.is_empty() {
+            writeln!(f, "{}", self.boxes)?;
+        }
+        Ok(())
+    }
+}
+
+/// all steps, separated by empty lines like in the puzzle
+pub fn walkthrough(steps: impl Iterator<Item = Step>) -> String {
+    steps
+        .map(|step| step.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(crate) fn show_boxes<F>(boxes: &Table<&str, Lens, F>) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut last_box_id = None;
+    for (box_id, _slot, label, lens) in boxes.iter() {
+        if last_box_id != Some(box_id) {
+            lines.push(format!("Box {box_id}:"));
+            last_box_id = Some(box_id);
+        }
+        let focal_length = lens.focal_length();
+        lines
+            .last_mut()
+            .unwrap()
+            .push_str(&format!(" [{label} {focal_length}]"));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn test_walkthrough() {
+        let expected = "\
+After \"rn=1\":
+Box 0: [rn 1]
+
+After \"cm-\":
+Box 0: [rn 1]
+
+After \"qp=3\":
+Box 0: [rn 1]
+Box 1: [qp 3]
+
+After \"cm=2\":
+Box 0: [rn 1] [cm 2]
+Box 1: [qp 3]
+
+After \"qp-\":
+Box 0: [rn 1] [cm 2]
+
+After \"pc=4\":
+Box 0: [rn 1] [cm 2]
+Box 3: [pc 4]
+
+After \"ot=9\":
+Box 0: [rn 1] [cm 2]
+Box 3: [pc 4] [ot 9]
+
+After \"ab=5\":
+Box 0: [rn 1] [cm 2]
+Box 3: [pc 4] [ot 9] [ab 5]
+
+After \"pc-\":
+Box 0: [rn 1] [cm 2]
+Box 3: [ot 9] [ab 5]
+
+After \"pc=6\":
+Box 0: [rn 1] [cm 2]
+Box 3: [ot 9] [ab 5] [pc 6]
+
+After \"ot=7\":
+Box 0: [rn 1] [cm 2]
+Box 3: [ot 7] [ab 5] [pc 6]
+";
+        assert_eq!(walkthrough(trace(EXAMPLE)), expected);
+    }
+
+    #[test]
+    fn test_stop_at_step() {
+        let steps: Vec<Step> = trace(EXAMPLE).take(3).collect();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[2].instruction, "qp=3");
+        assert_eq!(steps[2].boxes, "Box 0: [rn 1]\nBox 1: [qp 3]");
+
+        let step = trace(EXAMPLE).nth(4).unwrap();
+        assert_eq!(step.to_string(), "After \"qp-\":\nBox 0: [rn 1] [cm 2]\n");
+    }
+
+    #[test]
+    fn test_empty_boxes() {
+        let step = trace("ab=1,ab-").last().unwrap();
+        assert_eq!(step.to_string(), "After \"ab-\":\n");
+    }
+}
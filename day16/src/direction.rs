@@ -1,6 +1,4 @@
-use crate::map::Shape;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction {
     Up,
     Down,
@@ -9,6 +7,8 @@ pub enum Direction {
 }
 
 impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
     pub fn opposite(&self) -> Self {
         match self {
             Self::Up => Self::Down,
@@ -18,51 +18,36 @@ impl Direction {
         }
     }
 
-    pub fn follow(&self, shape: Shape) -> (Self, Option<Self>) {
-        match shape {
-            Shape::Empty => self.straight(),
-            Shape::MirrorForward => self.follow_mirror_forward(),
-            Shape::MirrorBackward => self.follow_mirror_backward(),
-            Shape::SplitterHorizontal => self.follow_splitter_horizontal(),
-            Shape::SplitterVertical => self.follow_splitter_vertical(),
-        }
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Self::Left | Self::Right)
     }
 
-    fn follow_splitter_horizontal(&self) -> (Self, Option<Self>) {
-        match self {
-            Self::Up | Self::Down => (Self::Left, Some(Self::Right)),
-            Self::Left | Self::Right => self.straight(),
+    /// both directions at a right angle
+    pub fn perpendicular(&self) -> [Self; 2] {
+        if self.is_horizontal() {
+            [Self::Up, Self::Down]
+        } else {
+            [Self::Left, Self::Right]
         }
     }
 
-    fn follow_splitter_vertical(&self) -> (Self, Option<Self>) {
+    /// reflected by a `/` mirror
+    pub fn mirror_forward(&self) -> Self {
         match self {
-            Self::Up | Self::Down => self.straight(),
-            Self::Left | Self::Right => (Self::Up, Some(Self::Down)),
-        }
-    }
-
-    fn straight(&self) -> (Self, Option<Self>) {
-        (*self, None)
-    }
-
-    fn follow_mirror_forward(&self) -> (Self, Option<Self>) {
-        let next = match self {
             Self::Up => Self::Right,
             Self::Right => Self::Up,
             Self::Down => Self::Left,
             Self::Left => Self::Down,
-        };
-        (next, None)
+        }
     }
 
-    fn follow_mirror_backward(&self) -> (Self, Option<Self>) {
-        let next = match self {
+    /// reflected by a `\` mirror
+    pub fn mirror_backward(&self) -> Self {
+        match self {
             Self::Up => Self::Left,
             Self::Left => Self::Up,
             Self::Down => Self::Right,
             Self::Right => Self::Down,
-        };
-        (next, None)
+        }
     }
 }
@@ -0,0 +1,307 @@
+use crate::direction::Direction;
+
+use std::collections::HashMap;
+
+/// An optical element on a tile, which decides where beams go next.
+pub trait Element: CloneElement + Send + Sync {
+    fn symbol(&self) -> char;
+
+    /// directions of all beams leaving the tile, for a beam moving in `direction` into it
+    fn outgoing(&self, direction: Direction) -> Vec<Direction>;
+
+    /// Called after every beam that hit the tile. Elements that change here have to say so in
+    /// [`Element::is_stateful`], as beams can't be cached for them.
+    fn hit(&mut self) {}
+
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    /// Tells the states of a stateful element apart, so a beam that comes back after any
+    /// element changed is not mistaken for a loop.
+    fn state(&self) -> usize {
+        0
+    }
+}
+
+pub trait CloneElement {
+    fn clone_element(&self) -> Box<dyn Element>;
+}
+
+impl<T: Element + Clone + 'static> CloneElement for T {
+    fn clone_element(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Element> {
+    fn clone(&self) -> Self {
+        self.clone_element()
+    }
+}
+
+/// `.`
+#[derive(Clone)]
+pub struct Empty;
+
+impl Element for Empty {
+    fn symbol(&self) -> char {
+        '.'
+    }
+    fn outgoing(&self, direction: Direction) -> Vec<Direction> {
+        vec![direction]
+    }
+}
+
+/// `/` if forward, `\` otherwise
+#[derive(Clone)]
+pub struct Mirror {
+    forward: bool,
+}
+
+impl Element for Mirror {
+    fn symbol(&self) -> char {
+        if self.forward {
+            '/'
+        } else {
+            '\\'
+        }
+    }
+    fn outgoing(&self, direction: Direction) -> Vec<Direction> {
+        if self.forward {
+            vec![direction.mirror_forward()]
+        } else {
+            vec![direction.mirror_backward()]
+        }
+    }
+}
+
+/// `-` if horizontal, `|` otherwise
+#[derive(Clone)]
+pub struct Splitter {
+    horizontal: bool,
+}
+
+impl Element for Splitter {
+    fn symbol(&self) -> char {
+        if self.horizontal {
+            '-'
+        } else {
+            '|'
+        }
+    }
+    fn outgoing(&self, direction: Direction) -> Vec<Direction> {
+        if direction.is_horizontal() == self.horizontal {
+            vec![direction]
+        } else {
+            direction.perpendicular().to_vec()
+        }
+    }
+}
+
+/// `#` swallows every beam
+#[derive(Clone)]
+pub struct Absorber;
+
+impl Element for Absorber {
+    fn symbol(&self) -> char {
+        '#'
+    }
+    fn outgoing(&self, _direction: Direction) -> Vec<Direction> {
+        Vec::new()
+    }
+}
+
+/// `>`, `<`, `^` or `v`, which only lets beams pass in its own direction
+#[derive(Clone)]
+pub struct Gate {
+    direction: Direction,
+}
+
+impl Element for Gate {
+    fn symbol(&self) -> char {
+        match self.direction {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        }
+    }
+    fn outgoing(&self, direction: Direction) -> Vec<Direction> {
+        if direction == self.direction {
+            vec![direction]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// `+` lets beams pass and also splits them to both sides
+#[derive(Clone)]
+pub struct TriSplitter;
+
+impl Element for TriSplitter {
+    fn symbol(&self) -> char {
+        '+'
+    }
+    fn outgoing(&self, direction: Direction) -> Vec<Direction> {
+        let [left, right] = direction.perpendicular();
+        vec![direction, left, right]
+    }
+}
+
+/// `%` starts as `/` and turns into `\` and back after every beam that hits it
+#[derive(Clone)]
+pub struct RotatingMirror {
+    mirror: Mirror,
+}
+
+impl Element for RotatingMirror {
+    fn symbol(&self) -> char {
+        '%'
+    }
+    fn outgoing(&self, direction: Direction) -> Vec<Direction> {
+        self.mirror.outgoing(direction)
+    }
+    fn hit(&mut self) {
+        self.mirror.forward = !self.mirror.forward;
+    }
+    fn is_stateful(&self) -> bool {
+        true
+    }
+    fn state(&self) -> usize {
+        usize::from(self.mirror.forward)
+    }
+}
+
+type Constructor = Box<dyn Fn() -> Box<dyn Element>>;
+
+/// All elements a map may consist of, by their symbol.
+pub struct Registry {
+    constructors: HashMap<char, Constructor>,
+}
+
+impl Registry {
+    pub fn empty() -> Self {
+        Self {
+            constructors: HashMap::new(),
+        }
+    }
+
+    /// replaces any element with the same symbol
+    pub fn register<E: Element + 'static>(&mut self, symbol: char, constructor: fn() -> E) {
+        let constructor = move || -> Box<dyn Element> { Box::new(constructor()) };
+        self.constructors.insert(symbol, Box::new(constructor));
+    }
+
+    pub fn create(&self, symbol: char) -> Option<Box<dyn Element>> {
+        self.constructors
+            .get(&symbol)
+            .map(|constructor| constructor())
+    }
+}
+
+impl Default for Registry {
+    /// the elements of the puzzle, and some more
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register('.', || Empty);
+        registry.register('/', || Mirror { forward: true });
+        registry.register('\\', || Mirror { forward: false });
+        registry.register('-', || Splitter { horizontal: true });
+        registry.register('|', || Splitter { horizontal: false });
+        registry.register('#', || Absorber);
+        registry.register('^', || Gate {
+            direction: Direction::Up,
+        });
+        registry.register('v', || Gate {
+            direction: Direction::Down,
+        });
+        registry.register('<', || Gate {
+            direction: Direction::Left,
+        });
+        registry.register('>', || Gate {
+            direction: Direction::Right,
+        });
+        registry.register('+', || TriSplitter);
+        registry.register('%', || RotatingMirror {
+            mirror: Mirror { forward: true },
+        });
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hamster::QuantumHamster;
+    use crate::map::{Coords, Map};
+    use crate::part1::fire_hamster;
+
+    #[test]
+    fn test_symbols_round_trip() {
+        let registry = Registry::default();
+        for symbol in "./\\-|#^v<>+%".chars() {
+            assert_eq!(registry.create(symbol).unwrap().symbol(), symbol);
+        }
+        assert!(registry.create('x').is_none());
+    }
+
+    #[test]
+    fn test_splitters() {
+        let registry = Registry::default();
+        let splitter = registry.create('-').unwrap();
+        assert_eq!(splitter.outgoing(Direction::Left), [Direction::Left]);
+        assert_eq!(
+            splitter.outgoing(Direction::Down),
+            [Direction::Left, Direction::Right]
+        );
+
+        let tri_splitter = registry.create('+').unwrap();
+        assert_eq!(tri_splitter.outgoing(Direction::Up).len(), 3);
+    }
+
+    /// `*` turns beams around
+    #[derive(Clone)]
+    struct Retroreflector;
+
+    impl Element for Retroreflector {
+        fn symbol(&self) -> char {
+            '*'
+        }
+        fn outgoing(&self, direction: Direction) -> Vec<Direction> {
+            vec![direction.opposite()]
+        }
+    }
+
+    #[test]
+    fn test_custom_element() {
+        let mut registry = Registry::default();
+        registry.register('*', || Retroreflector);
+        let map = Map::parse_with("..-.*", &registry);
+        assert_eq!(
+            map.get_field(Coords::new(4, 0)).unwrap().element().symbol(),
+            '*'
+        );
+
+        let hamster = QuantumHamster::new(Coords::new(0, 0), Direction::Right);
+        assert_eq!(fire_hamster(map, hamster), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown char *")]
+    fn test_unknown_element() {
+        Map::parse("..*");
+    }
+
+    #[test]
+    fn test_rotating_mirror() {
+        let mut mirror = Registry::default().create('%').unwrap();
+        assert!(mirror.is_stateful());
+        assert_eq!(mirror.outgoing(Direction::Right), [Direction::Up]);
+        mirror.hit();
+        assert_eq!(mirror.outgoing(Direction::Right), [Direction::Down]);
+        mirror.hit();
+        assert_eq!(mirror.outgoing(Direction::Right), [Direction::Up]);
+    }
+}
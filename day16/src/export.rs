@@ -0,0 +1,224 @@
+use crate::direction::Direction;
+use crate::map::{Coords, Field, Map};
+use crate::part2::{border_entries, Energizer};
+
+/// pixels per tile side in images
+const TILE_SIZE: usize = 4;
+const ENERGIZED_COLOR: [u8; 3] = [255, 200, 0];
+const DARK_COLOR: [u8; 3] = [20, 20, 40];
+/// from no border entries up to all of them
+const HEAT_RAMP: &[u8] = b" .:-=+*#%@";
+
+fn fields(map: &Map) -> impl Iterator<Item = impl Iterator<Item = &Field>> {
+    (0..map.height())
+        .map(move |y| (0..map.width()).map(move |x| map.get_field(Coords::new(x, y)).unwrap()))
+}
+
+fn text(map: &Map, symbol: impl Fn(&Field) -> char) -> String {
+    let mut out = String::new();
+    for line in fields(map) {
+        out.extend(line.map(&symbol));
+        out.push('\n');
+    }
+    out
+}
+
+/// Binary PPM image with one square of `TILE_SIZE` per tile.
+fn ppm(width: usize, height: usize, color: impl Fn(Coords) -> [u8; 3]) -> Vec<u8> {
+    let header = format!("P6\n{} {}\n255\n", width * TILE_SIZE, height * TILE_SIZE);
+    let mut image = header.into_bytes();
+    for y in 0..height * TILE_SIZE {
+        for x in 0..width * TILE_SIZE {
+            image.extend(color(Coords::new(x / TILE_SIZE, y / TILE_SIZE)));
+        }
+    }
+    image
+}
+
+/// energized tiles as `#`, like in the puzzle
+pub fn energized_text(map: &Map) -> String {
+    text(map, |field| if field.was_visited() { '#' } else { '.' })
+}
+
+pub fn energized_ppm(map: &Map) -> Vec<u8> {
+    ppm(map.width(), map.height(), |coords| {
+        if map.get_field(coords).unwrap().was_visited() {
+            ENERGIZED_COLOR
+        } else {
+            DARK_COLOR
+        }
+    })
+}
+
+/// Beams on empty tiles as arrows, or as number if several of them pass. All other tiles keep
+/// their symbol, like in the puzzle.
+pub fn arrows_text(map: &Map) -> String {
+    text(map, |field| {
+        let symbol = field.element().symbol();
+        if symbol != '.' {
+            return symbol;
+        }
+        match field.headings()[..] {
+            [] => '.',
+            [direction] => match direction {
+                Direction::Up => '^',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+                Direction::Right => '>',
+            },
+            ref headings => char::from_digit(headings.len() as u32, 10).unwrap(),
+        }
+    })
+}
+
+/// For every tile, how many of the border entries of part 2 energize it.
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    counts: Vec<usize>,
+    num_entries: usize,
+}
+
+impl Heatmap {
+    pub fn sweep(map: &Map) -> Self {
+        let energizer = Energizer::new(map);
+        let num_tiles = map.width() * map.height();
+        let mut counts = vec![0; num_tiles];
+        let mut num_entries = 0;
+        for (position, direction) in border_entries(map) {
+            let energized = energizer.energized(map, position, direction);
+            for (tile, count) in counts.iter_mut().enumerate() {
+                if energized.contains(tile) {
+                    *count += 1;
+                }
+            }
+            num_entries += 1;
+        }
+
+        Self {
+            width: map.width(),
+            height: map.height(),
+            counts,
+            num_entries,
+        }
+    }
+
+    pub fn count(&self, coords: Coords) -> usize {
+        self.counts[coords.y() * self.width + coords.x()]
+    }
+
+    /// share of all border entries, from 0 to 1
+    fn heat(&self, coords: Coords) -> f64 {
+        self.count(coords) as f64 / self.num_entries as f64
+    }
+
+    /// `' '` for tiles which are never energized, up to `'@'` for tiles which always are
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let heat = self.heat(Coords::new(x, y));
+                let level = (heat * (HEAT_RAMP.len() - 1) as f64).ceil() as usize;
+                out.push(HEAT_RAMP[level] as char);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// black over red and yellow to white
+    pub fn to_ppm(&self) -> Vec<u8> {
+        ppm(self.width, self.height, |coords| {
+            let heat = self.heat(coords) * 3.0;
+            let channel = |offset: f64| ((heat - offset).clamp(0.0, 1.0) * 255.0) as u8;
+            [channel(0.0), channel(1.0), channel(2.0)]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hamster::QuantumHamster;
+    use crate::part1::energize;
+
+    const EXAMPLE: &str = "\
+        .|...\\....\n\
+        |.-.\\.....\n\
+        .....|-...\n\
+        ........|.\n\
+        ..........\n\
+        .........\\\n\
+        ..../.\\\\..\n\
+        .-.-/..|..\n\
+        .|....-|.\\\n\
+        ..//.|....";
+
+    fn energized_example() -> Map {
+        let hamster = QuantumHamster::new(Coords::new(0, 0), Direction::Right);
+        energize(Map::parse(EXAMPLE), hamster)
+    }
+
+    #[test]
+    fn test_energized_text() {
+        let expected = "\
+            ######....\n\
+            .#...#....\n\
+            .#...#####\n\
+            .#...##...\n\
+            .#...##...\n\
+            .#...##...\n\
+            .#..####..\n\
+            ########..\n\
+            .#######..\n\
+            .#...#.#..\n";
+        assert_eq!(energized_text(&energized_example()), expected);
+    }
+
+    #[test]
+    fn test_arrows_text() {
+        let expected = "\
+            >|<<<\\....\n\
+            |v-.\\^....\n\
+            .v...|->>>\n\
+            .v...v^.|.\n\
+            .v...v^...\n\
+            .v...v^..\\\n\
+            .v../2\\\\..\n\
+            <->-/vv|..\n\
+            .|<<<2-|.\\\n\
+            .v//.|.v..\n";
+        assert_eq!(arrows_text(&energized_example()), expected);
+    }
+
+    #[test]
+    fn test_heatmap_matches_simulation() {
+        let map = Map::parse(EXAMPLE);
+        let heatmap = Heatmap::sweep(&map);
+
+        let energized_maps: Vec<Map> = border_entries(&map)
+            .map(|(position, direction)| {
+                energize(map.clone(), QuantumHamster::new(position, direction))
+            })
+            .collect();
+        for y in 0..map.height() {
+            for x in 0..map.width() {
+                let coords = Coords::new(x, y);
+                let expected = energized_maps
+                    .iter()
+                    .filter(|map| map.get_field(coords).unwrap().was_visited())
+                    .count();
+                assert_eq!(heatmap.count(coords), expected, "{coords:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_ppm_size() {
+        let heatmap = Heatmap::sweep(&Map::parse(EXAMPLE));
+        let header = "P6\n40 40\n255\n";
+        let image = heatmap.to_ppm();
+        assert!(image.starts_with(header.as_bytes()));
+        assert_eq!(image.len(), header.len() + 40 * 40 * 3);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::direction::Direction;
+use crate::map::{Coords, Map, TileSet};
+
+use std::collections::{HashMap, HashSet};
+
+/// Path of a beam until it gets split, absorbed, leaves the map or runs in a loop.
+struct Segment {
+    tiles: Vec<usize>,
+    /// tile of the splitting element and the direction the beam hit it in
+    splitter: Option<(usize, Direction)>,
+}
+
+impl Segment {
+    fn trace(map: &Map, mut position: Coords, mut direction: Direction) -> Self {
+        let mut seen = HashSet::new();
+        let mut tiles = Vec::new();
+
+        while let Some(tile) = map.tile_index(position) {
+            // mirrors alone can lead a beam in circles
+            if !seen.insert((tile, direction)) {
+                break;
+            }
+            tiles.push(tile);
+
+            let element = map.get_field(position).unwrap().element();
+            match element.outgoing(direction)[..] {
+                [] => break,
+                [next_direction] => direction = next_direction,
+                _ => {
+                    return Self {
+                        tiles,
+                        splitter: Some((tile, direction)),
+                    }
+                }
+            }
+            let Some(next_position) = position.move_into_direction(direction) else {
+                break;
+            };
+            position = next_position;
+        }
+
+        Self {
+            tiles,
+            splitter: None,
+        }
+    }
+}
+
+/// The contraption compiled into a graph: every element that splits a beam is a node, and its
+/// edges are the segments of the beams it sends out. Elements that send out different beams
+/// depending on where they are hit get a node per set of beams. Nodes that feed each other are
+/// collapsed into one component, which caches all tiles it energizes, including those of later
+/// components.
+pub struct BeamGraph {
+    num_tiles: usize,
+    /// component of the node hit on a tile in a direction
+    components: HashMap<(usize, Direction), usize>,
+    energized: Vec<TileSet>,
+}
+
+impl BeamGraph {
+    /// `None` for maps with stateful elements, whose beams can't be cached
+    pub fn new(map: &Map) -> Option<Self> {
+        if map.is_stateful() {
+            return None;
+        }
+        let num_tiles = map.width() * map.height();
+
+        let mut splitters: Vec<(Coords, Vec<Direction>)> = Vec::new();
+        let mut node_ids = HashMap::new();
+        for y in 0..map.height() {
+            for x in 0..map.width() {
+                let position = Coords::new(x, y);
+                let tile = map.tile_index(position).unwrap();
+                let element = map.get_field(position).unwrap().element();
+                let first_node = splitters.len();
+                for direction in Direction::ALL {
+                    let outgoing = element.outgoing(direction);
+                    if outgoing.len() < 2 {
+                        continue;
+                    }
+                    let existing = splitters[first_node..]
+                        .iter()
+                        .position(|(_, other_outgoing)| *other_outgoing == outgoing);
+                    let node = match existing {
+                        Some(index) => first_node + index,
+                        None => {
+                            splitters.push((position, outgoing));
+                            splitters.len() - 1
+                        }
+                    };
+                    node_ids.insert((tile, direction), node);
+                }
+            }
+        }
+
+        let mut node_tiles = Vec::with_capacity(splitters.len());
+        let mut edges = Vec::with_capacity(splitters.len());
+        for (position, outgoing) in &splitters {
+            let mut tiles = TileSet::new(num_tiles);
+            tiles.insert(map.tile_index(*position).unwrap());
+            let mut next_nodes = Vec::new();
+            for direction in outgoing {
+                let Some(start) = position.move_into_direction(*direction) else {
+                    continue;
+                };
+                let segment = Segment::trace(map, start, *direction);
+                segment.tiles.iter().for_each(|tile| tiles.insert(*tile));
+                next_nodes.extend(segment.splitter.map(|entry| node_ids[&entry]));
+            }
+            node_tiles.push(tiles);
+            edges.push(next_nodes);
+        }
+
+        let node_components = strongly_connected_components(&edges);
+
+        // components are numbered in reverse topological order, so all successors of a
+        // component are already done when it is its turn
+        let num_components = node_components.iter().max().map_or(0, |max| max + 1);
+        let mut members = vec![Vec::new(); num_components];
+        for (node, component) in node_components.iter().enumerate() {
+            members[*component].push(node);
+        }
+        let mut energized: Vec<TileSet> = Vec::with_capacity(num_components);
+        for (component, nodes) in members.iter().enumerate() {
+            let mut tiles = TileSet::new(num_tiles);
+            for node in nodes {
+                tiles.union_with(&node_tiles[*node]);
+                for next_node in &edges[*node] {
+                    let next_component = node_components[*next_node];
+                    if next_component != component {
+                        tiles.union_with(&energized[next_component]);
+                    }
+                }
+            }
+            energized.push(tiles);
+        }
+
+        let components = node_ids
+            .into_iter()
+            .map(|(entry, node)| (entry, node_components[node]))
+            .collect();
+        Some(Self {
+            num_tiles,
+            components,
+            energized,
+        })
+    }
+
+    /// all tiles energized by a beam entering `position` in `direction`
+    pub fn energized(&self, map: &Map, position: Coords, direction: Direction) -> TileSet {
+        let segment = Segment::trace(map, position, direction);
+        let mut tiles = match segment.splitter {
+            Some(entry) => self.energized[self.components[&entry]].clone(),
+            None => TileSet::new(self.num_tiles),
+        };
+        segment.tiles.iter().for_each(|tile| tiles.insert(*tile));
+        tiles
+    }
+}
+
+/// Tarjan's algorithm without recursion. Returns the component of each node, numbered in the
+/// order they are completed, which is a reverse topological order.
+fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+
+    let num_nodes = edges.len();
+    let mut index = vec![UNVISITED; num_nodes];
+    let mut low_link = vec![0; num_nodes];
+    let mut on_stack = vec![false; num_nodes];
+    let mut stack = Vec::new();
+    let mut components = vec![UNVISITED; num_nodes];
+    let mut num_components = 0;
+    let mut next_index = 0;
+
+    for root in 0..num_nodes {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // node and the position of its next edge to look at
+        let mut call_stack = vec![(root, 0)];
+        index[root] = next_index;
+        low_link[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, edge)) = call_stack.pop() {
+            if let Some(&next) = edges[node].get(edge) {
+                call_stack.push((node, edge + 1));
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    low_link[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    call_stack.push((next, 0));
+                } else if on_stack[next] {
+                    low_link[node] = low_link[node].min(index[next]);
+                }
+                continue;
+            }
+
+            // all edges are done, so the node can hand its low link to its parent
+            if let Some((parent, _)) = call_stack.last() {
+                low_link[*parent] = low_link[*parent].min(low_link[node]);
+            }
+
+            if low_link[node] == index[node] {
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    components[member] = num_components;
+                    if member == node {
+                        break;
+                    }
+                }
+                num_components += 1;
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hamster::QuantumHamster;
+    use crate::part1::fire_hamster;
+    use crate::part2::border_entries;
+
+    const EXAMPLE: &str = "\
+        .|...\\....\n\
+        |.-.\\.....\n\
+        .....|-...\n\
+        ........|.\n\
+        ..........\n\
+        .........\\\n\
+        ..../.\\\\..\n\
+        .-.-/..|..\n\
+        .|....-|.\\\n\
+        ..//.|....";
+
+    fn assert_matches_simulation(input: &str) {
+        let map = Map::parse(input);
+        let graph = BeamGraph::new(&map).unwrap();
+        for (position, direction) in border_entries(&map) {
+            let hamster = QuantumHamster::new(position, direction);
+            let expected = fire_hamster(map.clone(), hamster);
+            let energized = graph.energized(&map, position, direction);
+            assert_eq!(energized.len(), expected, "{position:?} {direction:?}");
+        }
+    }
+
+    #[test]
+    fn test_example() {
+        assert_matches_simulation(EXAMPLE);
+    }
+
+    /// 20 maps, where every tile is empty or one of `symbols` with the same probability
+    fn random_maps(symbols: &str) -> Vec<String> {
+        // xorshift, to not need any dependency
+        let mut state: u64 = 0x2545f4914f6cdd1d;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let symbols: Vec<char> = symbols.chars().collect();
+        let num_choices = 3 * symbols.len() as u64;
+        (0..20)
+            .map(|_| {
+                let map: Vec<String> = (0..25)
+                    .map(|_| {
+                        (0..30)
+                            .map(|_| {
+                                let choice = (random() % num_choices) as usize;
+                                symbols.get(choice).copied().unwrap_or('.')
+                            })
+                            .collect()
+                    })
+                    .collect();
+                map.join("\n")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_random_maps() {
+        for map in random_maps("/\\-|") {
+            assert_matches_simulation(&map);
+        }
+    }
+
+    #[test]
+    fn test_random_maps_with_new_elements() {
+        for map in random_maps("/\\-|#^v<>+") {
+            assert_matches_simulation(&map);
+        }
+    }
+
+    #[test]
+    fn test_tri_splitter_nodes() {
+        // the `+` sends out three beams, and different ones depending on the side it is hit
+        let input = "\
+            ..\\..\n\
+            ..+.-\n\
+            .....";
+        assert_matches_simulation(input);
+        let graph = BeamGraph::new(&Map::parse(input)).unwrap();
+        assert_eq!(graph.components.len(), 4 + 2);
+    }
+
+    #[test]
+    fn test_stateful_map_has_no_graph() {
+        assert!(BeamGraph::new(&Map::parse("..%..")).is_none());
+    }
+
+    #[test]
+    fn test_mirror_loop() {
+        // the beam from the splitter runs around the mirrors forever
+        let input = "\
+            ./..\\\n\
+            .....\n\
+            .\\-./\n\
+            .....";
+        assert_matches_simulation(input);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // 0 -> 1 <-> 2 -> 3, 3 -> 3
+        let edges = [vec![1], vec![2], vec![1, 3], vec![3]];
+        let components = strongly_connected_components(&edges);
+        assert_eq!(components[1], components[2]);
+        assert_ne!(components[0], components[1]);
+        assert!(components[3] < components[1]);
+        assert!(components[1] < components[0]);
+    }
+}
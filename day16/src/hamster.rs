@@ -14,17 +14,36 @@ impl QuantumHamster {
         }
     }
 
-    /// Quantum Hamster will try to reorient and follow the shape of the tile. As it is quantum,
-    /// it may go into superposition and return an evil parallel-universe-version of itself.
-    pub fn reorient(&mut self, map: &Map) -> Option<Self> {
-        let current_field = map.get_field(self.position).unwrap();
-        let (next_direction_a, next_direction_b) = self.direction.follow(current_field.shape());
-        self.direction = next_direction_a;
-        next_direction_b.map(|direction| Self::new(self.position, direction))
+    /// The beam enters its first tile from outside, so the tile is energized even if the
+    /// element there swallows it.
+    pub fn enter(&self, map: &mut Map) {
+        let field = map.get_field_mut(self.position).unwrap();
+        field.traverse(self.direction.opposite());
+    }
+
+    pub fn position(&self) -> Coords {
+        self.position
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Quantum Hamster will try to reorient and follow the element of the tile. As it is
+    /// quantum, it may go into superposition and return any number of evil
+    /// parallel-universe-versions of itself, or none at all.
+    pub fn reorient(self, map: &mut Map) -> Vec<Self> {
+        let element = map.get_field_mut(self.position).unwrap().element_mut();
+        let next_directions = element.outgoing(self.direction);
+        element.hit();
+        next_directions
+            .into_iter()
+            .map(|direction| Self::new(self.position, direction))
+            .collect()
     }
 
     /// Quantum hamster will try to walk into the direction it is facing, but it will interfere
-    /// negatively with itself at the end of the map and with tiles already visited by other hamsters.
+    /// negatively with itself at the end of the map.
     ///
     /// Luckily each [`QuantumHamster`] is evil by definition, so it's okay if it ceases to exist.
     /// Nobody will miss it.
@@ -33,11 +52,13 @@ impl QuantumHamster {
     /// government and conquering all the RAM.
     pub fn walk(mut self, map: &mut Map) -> Option<Self> {
         let current_field = map.get_field_mut(self.position).unwrap();
-        current_field.traverse(self.direction).ok()?;
+        // the beam passes through in this direction, even if it merges into another one
+        current_field.head(self.direction);
+        current_field.traverse(self.direction);
 
         let next_position = self.position.move_into_direction(self.direction)?;
         let next_field = map.get_field_mut(next_position)?;
-        next_field.traverse(self.direction.opposite()).ok()?;
+        next_field.traverse(self.direction.opposite());
 
         self.position = next_position;
         Some(self)
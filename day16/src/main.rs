@@ -1,9 +1,15 @@
 mod direction;
+mod element;
+mod export;
+mod graph;
 mod hamster;
 mod map;
 
 mod part1;
 mod part2;
+mod sweep;
+
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -14,6 +20,59 @@ pub fn read_input() -> String {
 
 fn main() {
     let input = read_input();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `day16 bench [threads] [samples]` compares the serial and the parallel sweep of part 2
+    if args.first().map(String::as_str) == Some("bench") {
+        let map = map::Map::parse(&input);
+        let num_threads = args
+            .get(1)
+            .map_or(part2::num_threads(), |arg| arg.parse().unwrap());
+        let num_samples = args.get(2).map_or(10, |arg| arg.parse().unwrap());
+        let energizer = part2::Energizer::new(&map);
+        println!("best: {}", sweep::parallel(&map, &energizer, num_threads));
+        println!("{}", sweep::Bench::run(&map, num_threads, num_samples));
+        return;
+    }
+
+    // `day16 export <energized|arrows|heatmap> [file.ppm]` shows the beams of part 1, or how
+    // often each tile is energized in part 2
+    if args.first().map(String::as_str) == Some("export") {
+        let map = map::Map::parse(&input);
+        let hamster =
+            hamster::QuantumHamster::new(map::Coords::new(0, 0), direction::Direction::Right);
+        let (text, image) = match args[1].as_str() {
+            "energized" => {
+                let map = part1::energize(map, hamster);
+                (
+                    export::energized_text(&map),
+                    Some(export::energized_ppm(&map)),
+                )
+            }
+            "arrows" => (export::arrows_text(&part1::energize(map, hamster)), None),
+            "heatmap" => {
+                let heatmap = export::Heatmap::sweep(&map);
+                (heatmap.to_text(), Some(heatmap.to_ppm()))
+            }
+            other => panic!("unknown export '{other}'"),
+        };
+        match (args.get(2), image) {
+            (Some(path), Some(image)) => std::fs::write(path, image).unwrap(),
+            (Some(_), None) => panic!("there is no image for {}", args[1]),
+            (None, _) => print!("{text}"),
+        }
+        return;
+    }
+
+    // `day16 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    let parse = || map::Map::parse(&input);
+    timings::run_from_args(&mut [
+        Stage::new("parse", parse),
+        Stage::with_setup("part1", parse, part1::fire_from_top_left),
+        Stage::with_setup("part2", parse, |map| part2::most_energized(&map)),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
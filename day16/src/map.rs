@@ -1,17 +1,86 @@
 use crate::direction::Direction;
+use crate::element::{Element, Registry};
+
+/// Set of tiles of a map, one bit per tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileSet {
+    words: Vec<u64>,
+}
+
+impl TileSet {
+    pub fn new(num_tiles: usize) -> Self {
+        Self {
+            words: vec![0; num_tiles.div_ceil(64)],
+        }
+    }
+
+    pub fn insert(&mut self, tile: usize) {
+        self.words[tile / 64] |= 1 << (tile % 64);
+    }
+
+    pub fn contains(&self, tile: usize) -> bool {
+        self.words[tile / 64] & (1 << (tile % 64)) != 0
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            *word |= other_word;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
 
 #[derive(Clone)]
 pub struct Map {
     fields: Vec<Vec<Field>>,
+    /// coords of all fields with stateful elements
+    stateful: Vec<Coords>,
 }
 
 impl Map {
     pub fn parse(s: &str) -> Self {
-        let fields = s
+        Self::parse_with(s, &Registry::default())
+    }
+
+    pub fn parse_with(s: &str, registry: &Registry) -> Self {
+        let parse_field = |c| {
+            let element = registry.create(c);
+            Field::new(element.unwrap_or_else(|| panic!("unknown char {c}")))
+        };
+        let fields: Vec<Vec<Field>> = s
             .lines()
-            .map(|line| line.chars().map(Field::parse).collect())
+            .map(|line| line.chars().map(parse_field).collect())
+            .collect();
+        let stateful = fields
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_x, field)| field.element.is_stateful())
+                    .map(move |(x, _field)| Coords::new(x, y))
+            })
             .collect();
-        Self { fields }
+        Self { fields, stateful }
+    }
+
+    /// if any element changes when hit, so beams depend on what happened before
+    pub fn is_stateful(&self) -> bool {
+        !self.stateful.is_empty()
+    }
+
+    /// states of all stateful elements, row by row
+    pub fn state(&self) -> Vec<usize> {
+        self.stateful
+            .iter()
+            .map(|coords| self.get_field(*coords).unwrap().element.state())
+            .collect()
     }
 
     pub fn get_field(&self, coords: Coords) -> Option<&Field> {
@@ -30,6 +99,24 @@ impl Map {
             .count()
     }
 
+    pub fn visited_tiles(&self) -> TileSet {
+        let mut tiles = TileSet::new(self.width() * self.height());
+        for (tile, field) in self.fields.iter().flatten().enumerate() {
+            if field.was_visited() {
+                tiles.insert(tile);
+            }
+        }
+        tiles
+    }
+
+    /// row by row, `None` for coords outside of the map
+    pub fn tile_index(&self, coords: Coords) -> Option<usize> {
+        if coords.x >= self.width() || coords.y >= self.height() {
+            return None;
+        }
+        Some(coords.y * self.width() + coords.x)
+    }
+
     pub fn width(&self) -> usize {
         self.fields[0].len()
     }
@@ -41,35 +128,49 @@ impl Map {
 
 #[derive(Clone)]
 pub struct Field {
-    shape: Shape,
+    element: Box<dyn Element>,
     traversed: Traversed,
+    /// directions in which beams left the field
+    headings: Traversed,
 }
 impl Field {
-    fn parse(c: char) -> Self {
-        let shape = Shape::parse(c);
+    fn new(element: Box<dyn Element>) -> Self {
         let traversed = Traversed::default();
-        Self { shape, traversed }
+        let headings = Traversed::default();
+        Self {
+            element,
+            traversed,
+            headings,
+        }
     }
 
-    pub fn shape(&self) -> Shape {
-        self.shape
+    pub fn element(&self) -> &dyn Element {
+        self.element.as_ref()
     }
 
-    /// Returns error if traversial was already done
-    pub fn traverse(&mut self, direction: Direction) -> Result<(), ()> {
-        if self.traversed.was_traversed(direction) {
-            return Err(());
-        }
+    pub fn element_mut(&mut self) -> &mut dyn Element {
+        self.element.as_mut()
+    }
+
+    /// marks the side of the field in `direction` as crossed by a beam
+    pub fn traverse(&mut self, direction: Direction) {
         self.traversed.traverse(direction);
-        Ok(())
     }
 
     pub fn was_visited(&self) -> bool {
         self.traversed.was_visited()
     }
+
+    pub fn head(&mut self, direction: Direction) {
+        self.headings.traverse(direction);
+    }
+
+    pub fn headings(&self) -> Vec<Direction> {
+        self.headings.directions()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Coords {
     x: usize,
     y: usize,
@@ -80,6 +181,14 @@ impl Coords {
         Coords { x, y }
     }
 
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
     pub fn move_into_direction(&self, direction: Direction) -> Option<Self> {
         let mut ret = *self;
         match direction {
@@ -104,28 +213,6 @@ impl Coords {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Shape {
-    Empty,
-    MirrorForward,
-    MirrorBackward,
-    SplitterHorizontal,
-    SplitterVertical,
-}
-
-impl Shape {
-    fn parse(c: char) -> Self {
-        match c {
-            '.' => Self::Empty,
-            '/' => Self::MirrorForward,
-            '\\' => Self::MirrorBackward,
-            '-' => Self::SplitterHorizontal,
-            '|' => Self::SplitterVertical,
-            _ => panic!("unknown char {c}"),
-        }
-    }
-}
-
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct Traversed {
     up: bool,
@@ -156,4 +243,11 @@ impl Traversed {
     fn was_visited(&self) -> bool {
         self.up || self.down || self.left || self.right
     }
+
+    fn directions(&self) -> Vec<Direction> {
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| self.was_traversed(*direction))
+            .collect()
+    }
 }
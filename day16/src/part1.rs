@@ -2,8 +2,13 @@ use crate::direction::Direction;
 use crate::hamster::QuantumHamster;
 use crate::map::{Coords, Map};
 
+use std::collections::HashSet;
+
 pub fn run(input: &str) -> usize {
-    let map = Map::parse(input);
+    fire_from_top_left(Map::parse(input))
+}
+
+pub fn fire_from_top_left(map: Map) -> usize {
     let starting_position = Coords::new(0, 0);
     let starting_direction = Direction::Right;
     let hamster = QuantumHamster::new(starting_position, starting_direction);
@@ -11,21 +16,29 @@ pub fn run(input: &str) -> usize {
     fire_hamster(map, hamster)
 }
 
-pub fn fire_hamster(mut map: Map, hamster: QuantumHamster) -> usize {
+pub fn fire_hamster(map: Map, hamster: QuantumHamster) -> usize {
+    energize(map, hamster).num_visited_fields()
+}
+
+/// The map with all fields marked, that the beam passed. Split beams are followed depth-first,
+/// the last one split off first, which also decides the order in which stateful elements are hit.
+///
+/// A beam ends when it reaches a tile in the same direction as a beam before, while all
+/// stateful elements are in the same state as back then, as it would only repeat that beam.
+pub fn energize(mut map: Map, hamster: QuantumHamster) -> Map {
+    hamster.enter(&mut map);
     let mut hamsters = vec![hamster];
+    let mut seen = HashSet::new();
 
-    while let Some(mut hamster) = hamsters.pop() {
-        if let Some(new_hamster) = hamster.reorient(&map) {
-            hamsters.push(new_hamster);
+    while let Some(hamster) = hamsters.pop() {
+        if !seen.insert((hamster.position(), hamster.direction(), map.state())) {
+            continue;
         }
-        while let Some(mut same_hamster) = hamster.walk(&mut map) {
-            if let Some(new_hamster) = same_hamster.reorient(&map) {
-                hamsters.push(new_hamster);
-            }
-            hamster = same_hamster;
+        for new_hamster in hamster.reorient(&mut map) {
+            hamsters.extend(new_hamster.walk(&mut map));
         }
     }
-    map.num_visited_fields()
+    map
 }
 
 #[cfg(test)]
@@ -48,4 +61,61 @@ mod tests {
     fn test_example() {
         assert_eq!(run(EXAMPLE), 46);
     }
+
+    #[test]
+    fn test_new_elements() {
+        // gates only let beams pass in their own direction, and the absorber swallows them
+        let input = "\
+            .+..\\\n\
+            ....v\n\
+            .<..#\n\
+            .....";
+        let map = Map::parse(input);
+        let hamster = QuantumHamster::new(Coords::new(0, 0), Direction::Right);
+        let energized = energize(map, hamster);
+        let visited: Vec<String> = (0..energized.height())
+            .map(|y| {
+                (0..energized.width())
+                    .map(|x| match energized.get_field(Coords::new(x, y)) {
+                        Some(field) if field.was_visited() => '#',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(visited, ["#####", ".#..#", ".#..#", "....."]);
+    }
+
+    #[test]
+    fn test_beam_starting_on_absorber() {
+        let map = Map::parse("#..");
+        let hamster = QuantumHamster::new(Coords::new(0, 0), Direction::Right);
+        assert_eq!(fire_hamster(map, hamster), 1);
+    }
+
+    #[test]
+    fn test_rotating_mirror() {
+        // the beam returns to the mirror twice: the first time it is a `\` and sends the beam
+        // around the loop again, the second time it is a `/` again and sends it down
+        let input = "\
+            /\\.|\n\
+            %/.|\n\
+            ....";
+        let map = Map::parse(input);
+        let hamster = QuantumHamster::new(Coords::new(0, 1), Direction::Right);
+        assert_eq!(fire_hamster(map, hamster), 5);
+    }
+
+    #[test]
+    fn test_rotating_mirror_after_loop() {
+        // the beam loops back into the mirror from the same side, but now it is a `\` and
+        // sends the beam down instead of around the loop again
+        let input = "\
+            /\\.\n\
+            \\%.\n\
+            ...";
+        let map = Map::parse(input);
+        let hamster = QuantumHamster::new(Coords::new(1, 1), Direction::Right);
+        assert_eq!(fire_hamster(map, hamster), 5);
+    }
 }
@@ -1,59 +1,88 @@
 use crate::direction::Direction;
+use crate::graph::BeamGraph;
 use crate::hamster::QuantumHamster;
-use crate::map::{Coords, Map};
-use crate::part1::fire_hamster;
+use crate::map::{Coords, Map, TileSet};
+use crate::part1::energize;
+use crate::sweep;
 
 pub fn run(input: &str) -> usize {
-    let map = Map::parse(input);
-
-    hamsters_from_above(&map)
-        .chain(hamsters_from_below(&map))
-        .chain(hamsters_from_left(&map))
-        .chain(hamsters_from_right(&map))
-        .map(|hamster| fire_hamster(map.clone(), hamster))
-        .max()
-        .unwrap()
+    most_energized(&Map::parse(input))
 }
 
-fn hamsters_from_above(map: &Map) -> impl Iterator<Item = QuantumHamster> {
+pub fn most_energized(map: &Map) -> usize {
+    let energizer = Energizer::new(map);
+    sweep::parallel(map, &energizer, num_threads()).count
+}
+
+pub fn num_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, usize::from)
+}
+
+/// Uses the beam graph where possible, and simulates every beam on maps with stateful elements.
+pub enum Energizer {
+    Graph(BeamGraph),
+    Simulation,
+}
+
+impl Energizer {
+    pub fn new(map: &Map) -> Self {
+        match BeamGraph::new(map) {
+            Some(graph) => Self::Graph(graph),
+            None => Self::Simulation,
+        }
+    }
+
+    pub fn energized(&self, map: &Map, position: Coords, direction: Direction) -> TileSet {
+        match self {
+            Self::Graph(graph) => graph.energized(map, position, direction),
+            Self::Simulation => {
+                let hamster = QuantumHamster::new(position, direction);
+                energize(map.clone(), hamster).visited_tiles()
+            }
+        }
+    }
+}
+
+/// every tile on the border, with the direction pointing into the map
+pub fn border_entries(map: &Map) -> impl Iterator<Item = (Coords, Direction)> {
+    entries_from_above(map)
+        .chain(entries_from_below(map))
+        .chain(entries_from_left(map))
+        .chain(entries_from_right(map))
+}
+
+fn entries_from_above(map: &Map) -> impl Iterator<Item = (Coords, Direction)> {
     let range = 0..map.width();
     let y = 0; // first row
     let direction = Direction::Down;
-    range
-        .map(move |x| Coords::new(x, y))
-        .map(move |position| QuantumHamster::new(position, direction))
+    range.map(move |x| (Coords::new(x, y), direction))
 }
 
-fn hamsters_from_below(map: &Map) -> impl Iterator<Item = QuantumHamster> {
+fn entries_from_below(map: &Map) -> impl Iterator<Item = (Coords, Direction)> {
     let range = 0..map.width();
     let y = map.height() - 1; // last row
     let direction = Direction::Up;
-    range
-        .map(move |x| Coords::new(x, y))
-        .map(move |position| QuantumHamster::new(position, direction))
+    range.map(move |x| (Coords::new(x, y), direction))
 }
 
-fn hamsters_from_left(map: &Map) -> impl Iterator<Item = QuantumHamster> {
+fn entries_from_left(map: &Map) -> impl Iterator<Item = (Coords, Direction)> {
     let range = 0..map.height();
     let x = 0; // first col
     let direction = Direction::Right;
-    range
-        .map(move |y| Coords::new(x, y))
-        .map(move |position| QuantumHamster::new(position, direction))
+    range.map(move |y| (Coords::new(x, y), direction))
 }
 
-fn hamsters_from_right(map: &Map) -> impl Iterator<Item = QuantumHamster> {
+fn entries_from_right(map: &Map) -> impl Iterator<Item = (Coords, Direction)> {
     let range = 0..map.height();
     let x = map.width() - 1; // last col
     let direction = Direction::Left;
-    range
-        .map(move |y| Coords::new(x, y))
-        .map(move |position| QuantumHamster::new(position, direction))
+    range.map(move |y| (Coords::new(x, y), direction))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::part1::fire_hamster;
 
     const EXAMPLE: &str = "\
         .|...\\....\n\
@@ -70,11 +99,7 @@ mod tests {
     #[test]
     fn hamsters_from_the_side_count_is_correct() {
         let map = Map::parse(EXAMPLE);
-        let num_hamsters = hamsters_from_above(&map)
-            .chain(hamsters_from_below(&map))
-            .chain(hamsters_from_left(&map))
-            .chain(hamsters_from_right(&map))
-            .count();
+        let num_hamsters = border_entries(&map).count();
         let expected = 2 * (map.width() + map.height());
         assert_eq!(num_hamsters, expected);
     }
@@ -84,6 +109,21 @@ mod tests {
         assert_eq!(run(EXAMPLE), 51);
     }
 
+    #[test]
+    fn test_stateful_map_is_simulated() {
+        let input = "\
+            .%\\\n\
+            .|/\n\
+            ...";
+        let map = Map::parse(input);
+        assert!(matches!(Energizer::new(&map), Energizer::Simulation));
+
+        // From the left of the middle row, the splitter sends a beam up around the loop back
+        // into the splitter, which sends it up into the mirror again. By then the mirror turned
+        // into a `\`, so the beam leaves to the left and energizes all but two tiles.
+        assert_eq!(run(input), 7);
+    }
+
     #[test]
     fn test_example_right_hamster() {
         let map = Map::parse(EXAMPLE);
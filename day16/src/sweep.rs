@@ -0,0 +1,183 @@
+use crate::direction::Direction;
+use crate::map::{Coords, Map};
+use crate::part2::{border_entries, Energizer};
+
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
+/// The border entry energizing the most tiles. On ties the entry that comes first in
+/// [`border_entries`] wins, no matter how the sweep was split up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Best {
+    pub count: usize,
+    pub position: Coords,
+    pub direction: Direction,
+}
+
+impl std::fmt::Display for Best {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} tiles, entering at ({}, {}) heading {:?}",
+            self.count,
+            self.position.x(),
+            self.position.y(),
+            self.direction
+        )
+    }
+}
+
+/// entry index and count of the best entry among `entries`
+fn best_of(
+    map: &Map,
+    energizer: &Energizer,
+    entries: &[(usize, (Coords, Direction))],
+) -> Option<(usize, usize)> {
+    entries
+        .iter()
+        .map(|(index, (position, direction))| {
+            let count = energizer.energized(map, *position, *direction).len();
+            (*index, count)
+        })
+        .max_by_key(|(index, count)| (*count, Reverse(*index)))
+}
+
+fn to_best(entries: &[(usize, (Coords, Direction))], (index, count): (usize, usize)) -> Best {
+    let (position, direction) = entries[index].1;
+    Best {
+        count,
+        position,
+        direction,
+    }
+}
+
+pub fn serial(map: &Map, energizer: &Energizer) -> Best {
+    let entries: Vec<_> = border_entries(map).enumerate().collect();
+    let best = best_of(map, energizer, &entries).unwrap();
+    to_best(&entries, best)
+}
+
+/// Splits the entries into one contiguous chunk per thread.
+pub fn parallel(map: &Map, energizer: &Energizer, num_threads: usize) -> Best {
+    assert_ne!(num_threads, 0, "sweep needs at least one thread");
+    let entries: Vec<_> = border_entries(map).enumerate().collect();
+    let chunk_size = entries.len().div_ceil(num_threads);
+
+    let chunk_bests: Vec<(usize, usize)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = entries
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| best_of(map, energizer, chunk).unwrap()))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    let best = chunk_bests
+        .into_iter()
+        .max_by_key(|(index, count)| (*count, Reverse(*index)))
+        .unwrap();
+    to_best(&entries, best)
+}
+
+/// Fastest of some runs of both sweeps, after one run of each to warm up.
+pub struct Bench {
+    pub num_threads: usize,
+    pub serial: Duration,
+    pub parallel: Duration,
+}
+
+impl Bench {
+    pub fn run(map: &Map, num_threads: usize, num_samples: usize) -> Self {
+        let energizer = Energizer::new(map);
+        let fastest = |sweep: &dyn Fn() -> Best| {
+            sweep();
+            (0..num_samples)
+                .map(|_| {
+                    let start = Instant::now();
+                    sweep();
+                    start.elapsed()
+                })
+                .min()
+                .unwrap()
+        };
+
+        Self {
+            num_threads,
+            serial: fastest(&|| serial(map, &energizer)),
+            parallel: fastest(&|| parallel(map, &energizer, num_threads)),
+        }
+    }
+
+    pub fn speedup(&self) -> f64 {
+        self.serial.as_secs_f64() / self.parallel.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for Bench {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "serial {:?}, parallel on {} threads {:?}, speedup {:.2}",
+            self.serial,
+            self.num_threads,
+            self.parallel,
+            self.speedup()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        .|...\\....\n\
+        |.-.\\.....\n\
+        .....|-...\n\
+        ........|.\n\
+        ..........\n\
+        .........\\\n\
+        ..../.\\\\..\n\
+        .-.-/..|..\n\
+        .|....-|.\\\n\
+        ..//.|....";
+
+    #[test]
+    fn test_example() {
+        let map = Map::parse(EXAMPLE);
+        let energizer = Energizer::new(&map);
+        let expected = Best {
+            count: 51,
+            position: Coords::new(3, 0),
+            direction: Direction::Down,
+        };
+        assert_eq!(serial(&map, &energizer), expected);
+        for num_threads in [1, 2, 3, 7, 40, 100] {
+            assert_eq!(parallel(&map, &energizer, num_threads), expected);
+        }
+    }
+
+    #[test]
+    fn test_ties_go_to_the_first_entry() {
+        // every entry energizes a whole row or column
+        let map = Map::parse("...\n...\n...");
+        let energizer = Energizer::new(&map);
+        let expected = Best {
+            count: 3,
+            position: Coords::new(0, 0),
+            direction: Direction::Down,
+        };
+        for num_threads in 1..=12 {
+            assert_eq!(parallel(&map, &energizer, num_threads), expected);
+        }
+    }
+
+    #[test]
+    fn test_stateful_map() {
+        let map = Map::parse(".%.|\n....\n.-%.");
+        let energizer = Energizer::new(&map);
+        assert_eq!(parallel(&map, &energizer, 4), serial(&map, &energizer));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::map::{Coords, Direction, Map};
+use crate::search::State;
 
 pub struct QuantumHamster<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8> {
     position: Coords,
@@ -41,6 +42,18 @@ impl<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>
         }
     }
 
+    pub fn state(&self) -> State {
+        State {
+            position: self.position,
+            direction: self.direction,
+            num_straight_walks: self.num_straight_walks,
+        }
+    }
+
+    pub fn heat_trace(&self) -> u32 {
+        self.heat_trace
+    }
+
     fn superposition(&self, new_direction: Direction) -> Self {
         Self {
             position: self.position,
@@ -51,11 +64,8 @@ impl<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>
     }
 
     fn take_heat_and_leave_trace(mut self, map: &mut Map<MAX_STRAIGHT_WALKS>) -> Option<Self> {
-        let field = map.get_field_mut(self.position)?;
-        self.heat_trace += field.heat_loss();
-        field
-            .leave_trace(self.direction, self.num_straight_walks, self.heat_trace)
-            .ok()?;
+        self.heat_trace += map.get_field(self.position)?.heat_loss();
+        map.leave_trace(&self.state(), self.heat_trace).ok()?;
         Some(self)
     }
 
@@ -82,6 +92,10 @@ impl<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>
         (hamster_left, hamster_right, hamster_straight)
     }
 
+    pub fn next_position(&self) -> Option<Coords> {
+        self.position.move_into_direction(self.direction)
+    }
+
     /// Quantum hamster will try to walk into the direction it is facing, but its wave function
     /// will cease to exist on the edges of the map and when encountering a trail of another
     /// quantum hamster that was more efficient.
@@ -92,8 +106,7 @@ impl<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>
     /// Furthermore, this prevents the evil hamsters from clinging together, forming a fascist
     /// government and conquering all the RAM.
     pub fn walk(mut self, map: &mut Map<MAX_STRAIGHT_WALKS>) -> Option<Self> {
-        let next_position = self.position.move_into_direction(self.direction)?;
-        self.position = next_position;
+        self.position = self.next_position()?;
         self.num_straight_walks += 1;
 
         self = self.take_heat_and_leave_trace(map)?;
@@ -3,6 +3,10 @@ mod map;
 
 mod part1;
 mod part2;
+mod search;
+mod visual;
+
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -11,8 +15,90 @@ pub fn read_input() -> String {
     fs::read_to_string(input_path).unwrap()
 }
 
+/// `distances` prints the smallest heat loss to stop on every field from the top left,
+/// `cost <x> <y>` the one to a single field, and `across` the one from any field on the left
+/// edge to any field on the right edge. `watch [every] [delay in ms]` shows the search while
+/// it runs.
+fn query<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>(input: &str, args: &[String]) {
+    use map::{Coords, Direction, Map};
+    use search::{search, State};
+
+    let map = Map::<MAX_STRAIGHT_WALKS>::parse(input);
+    let top_left = [
+        (Coords::new(0, 0), Direction::Down),
+        (Coords::new(0, 0), Direction::Right),
+    ];
+    match args[0].as_str() {
+        "distances" => {
+            let result = search::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map, &top_left, &[]);
+            print!("{}", result.distances);
+        }
+        "cost" => {
+            let x = args[1].parse().unwrap();
+            let y = args[2].parse().unwrap();
+            let result = search::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map, &top_left, &[]);
+            match result.distances.get(Coords::new(x, y)) {
+                Some(cost) => println!("{cost}"),
+                None => println!("unreachable"),
+            }
+        }
+        "across" => {
+            let right_edge = map.width() - 1;
+            let starts = search::left_edge(&map);
+            let is_goal = |state: &State| {
+                state.position.x() == right_edge && state.num_straight_walks >= MIN_STRAIGHT_WALKS
+            };
+            let result =
+                search::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map, &starts, &[&is_goal]);
+            match result.goal_costs[0] {
+                Some(cost) => println!("{cost}"),
+                None => println!("unreachable"),
+            }
+        }
+        "watch" => {
+            let every = args.get(1).map_or(100, |every| every.parse().unwrap());
+            let delay = args.get(2).map_or(50, |delay| delay.parse().unwrap());
+            let delay = std::time::Duration::from_millis(delay);
+            let mut stdout = std::io::stdout();
+            let result = visual::watch::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(
+                map,
+                every,
+                delay,
+                &mut stdout,
+            )
+            .unwrap();
+            println!("{}", result.stats);
+        }
+        other => panic!("unknown query '{other}'"),
+    }
+}
+
 fn main() {
     let input = read_input();
+
+    // `day17 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || {
+            (map::Map::<3>::parse(&input), map::Map::<10>::parse(&input))
+        }),
+        Stage::with_setup("part1", || map::Map::parse(&input), part1::least_heat_loss),
+        Stage::with_setup("part2", || map::Map::parse(&input), part2::least_heat_loss),
+    ]);
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `day17 <distances|cost|across|watch> <1|2> [args]` answers queries with the rules of a part
+    if let Some(part) = args.get(1) {
+        let mut query_args = args.clone();
+        query_args.remove(1);
+        match part.as_str() {
+            "1" => query::<0, 3>(&input, &query_args),
+            "2" => query::<4, 10>(&input, &query_args),
+            other => panic!("unknown part '{other}'"),
+        }
+        return;
+    }
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
@@ -1,21 +1,26 @@
+use crate::search::State;
+
 pub struct Map<const MAX_STRAIGHT_WALKS: u8> {
-    fields: Vec<Vec<Field<MAX_STRAIGHT_WALKS>>>,
+    fields: Vec<Vec<Field>>,
+    traces: StateTable<MAX_STRAIGHT_WALKS>,
 }
 impl<const MAX_STRAIGHT_WALKS: u8> Map<MAX_STRAIGHT_WALKS> {
     pub fn parse(s: &str) -> Self {
-        let fields = s
+        let fields: Vec<Vec<Field>> = s
             .lines()
             .map(|line| line.chars().map(Field::parse).collect())
             .collect();
-        Self { fields }
+        let traces = StateTable::new(fields[0].len(), fields.len());
+        Self { fields, traces }
     }
 
-    pub fn get_field(&self, coords: Coords) -> Option<&Field<MAX_STRAIGHT_WALKS>> {
+    pub fn get_field(&self, coords: Coords) -> Option<&Field> {
         self.fields.get(coords.y)?.get(coords.x)
     }
 
-    pub fn get_field_mut(&mut self, coords: Coords) -> Option<&mut Field<MAX_STRAIGHT_WALKS>> {
-        self.fields.get_mut(coords.y)?.get_mut(coords.x)
+    /// set a new smallest hamster trace. Returns an error if the new trace is not smaller.
+    pub fn leave_trace(&mut self, state: &State, new_trace: u32) -> Result<(), ()> {
+        self.traces.leave_trace(state, new_trace)
     }
 
     pub fn width(&self) -> usize {
@@ -27,75 +32,72 @@ impl<const MAX_STRAIGHT_WALKS: u8> Map<MAX_STRAIGHT_WALKS> {
     }
 }
 
-pub struct Field<const MAX_STRAIGHT_WALKS: u8> {
+pub struct Field {
     heat_loss: u32,
-    trace_cache: TraceCache<MAX_STRAIGHT_WALKS>,
 }
 
-impl<const MAX_STRAIGHT_WALKS: u8> Field<MAX_STRAIGHT_WALKS> {
+impl Field {
     fn parse(c: char) -> Self {
         Self {
             heat_loss: c.to_digit(10).expect("unknown char '{c}'"),
-            trace_cache: TraceCache::default(),
         }
     }
 
     pub fn heat_loss(&self) -> u32 {
         self.heat_loss
     }
-
-    pub fn smallest_trace(&self) -> Option<u32> {
-        self.trace_cache.smallest_trace()
-    }
-
-    /// set a new smallest hamster trace. Returns an error if the new trace is not smaller.
-    pub fn leave_trace(
-        &mut self,
-        direction: Direction,
-        num_straight_walks: u8,
-        new_trace: u32,
-    ) -> Result<(), ()> {
-        self.trace_cache
-            .leave_trace(direction, num_straight_walks, new_trace)
-    }
 }
 
-struct TraceCache<const MAX_STRAIGHT_WALKS: u8> {
+/// Smallest hamster trace of every state on the map, in one flat table. Hamsters walk straight
+/// `0..=MAX_STRAIGHT_WALKS` times in a row, so each field has that many states per direction.
+struct StateTable<const MAX_STRAIGHT_WALKS: u8> {
+    width: usize,
+    height: usize,
     traces: Vec<Option<u32>>,
 }
 
-impl<const MAX_STRAIGHT_WALKS: u8> Default for TraceCache<MAX_STRAIGHT_WALKS> {
-    fn default() -> Self {
-        let size = usize::from(Self::TRACE_CACHE_SIZE);
-        let traces = vec![None; size];
-        Self { traces }
-    }
-}
+impl<const MAX_STRAIGHT_WALKS: u8> StateTable<MAX_STRAIGHT_WALKS> {
+    const POSSIBLE_DIRECTIONS: usize = 4;
+    const POSSIBLE_WALKS: usize = MAX_STRAIGHT_WALKS as usize + 1;
+    const STATES_PER_FIELD: usize = Self::POSSIBLE_DIRECTIONS * Self::POSSIBLE_WALKS;
 
-impl<const MAX_STRAIGHT_WALKS: u8> TraceCache<MAX_STRAIGHT_WALKS> {
-    const POSSIBLE_DIRECTIONS: u8 = 4;
-    const TRACE_CACHE_SIZE: u8 = Self::POSSIBLE_DIRECTIONS * (MAX_STRAIGHT_WALKS + 1);
+    fn new(width: usize, height: usize) -> Self {
+        let traces = vec![None; width * height * Self::STATES_PER_FIELD];
+        Self {
+            width,
+            height,
+            traces,
+        }
+    }
 
-    fn calc_trace_index(direction: Direction, num_straight_walks: u8) -> usize {
-        let directions_id = match direction {
+    /// `None` for states outside of the map or with too many straight walks
+    fn index(&self, state: &State) -> Option<usize> {
+        let Coords { x, y } = state.position;
+        let walks_id = usize::from(state.num_straight_walks);
+        if x >= self.width || y >= self.height || walks_id >= Self::POSSIBLE_WALKS {
+            return None;
+        }
+        let directions_id = match state.direction {
             Direction::Up => 0,
             Direction::Down => 1,
             Direction::Left => 2,
             Direction::Right => 3,
         };
-        let max_straight_walks = usize::from(MAX_STRAIGHT_WALKS);
-        let walks_id = usize::from(num_straight_walks);
-        directions_id * max_straight_walks + walks_id
-    }
-
-    pub fn leave_trace(
-        &mut self,
-        direction: Direction,
-        num_straight_walks: u8,
-        new_trace: u32,
-    ) -> Result<(), ()> {
-        let index = Self::calc_trace_index(direction, num_straight_walks);
-        let maybe_old_trace = self.traces.get_mut(index).unwrap();
+        let field_id = y * self.width + x;
+        let state_id = directions_id * Self::POSSIBLE_WALKS + walks_id;
+        Some(field_id * Self::STATES_PER_FIELD + state_id)
+    }
+
+    #[cfg(test)]
+    fn get(&self, state: &State) -> Option<u32> {
+        self.traces[self.index(state)?]
+    }
+
+    fn leave_trace(&mut self, state: &State, new_trace: u32) -> Result<(), ()> {
+        let index = self
+            .index(state)
+            .unwrap_or_else(|| panic!("{state:?} is outside of the state table"));
+        let maybe_old_trace = &mut self.traces[index];
         if let Some(old_trace) = maybe_old_trace {
             if new_trace >= *old_trace {
                 return Err(());
@@ -104,13 +106,6 @@ impl<const MAX_STRAIGHT_WALKS: u8> TraceCache<MAX_STRAIGHT_WALKS> {
         *maybe_old_trace = Some(new_trace);
         Ok(())
     }
-
-    pub fn smallest_trace(&self) -> Option<u32> {
-        self.traces
-            .iter()
-            .filter_map(|trace| trace.as_ref().copied())
-            .min()
-    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -124,6 +119,14 @@ impl Coords {
         Coords { x, y }
     }
 
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
     pub fn move_into_direction(&self, direction: Direction) -> Option<Self> {
         let mut ret = *self;
         match direction {
@@ -175,3 +178,79 @@ impl Direction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    const DIRECTIONS: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    fn all_states(width: usize, height: usize, max_straight_walks: u8) -> Vec<State> {
+        let mut states = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                for direction in DIRECTIONS {
+                    for num_straight_walks in 0..=max_straight_walks {
+                        let position = Coords::new(x, y);
+                        states.push(State {
+                            position,
+                            direction,
+                            num_straight_walks,
+                        });
+                    }
+                }
+            }
+        }
+        states
+    }
+
+    #[test]
+    fn test_state_table_has_no_collisions() {
+        let (width, height) = (5, 3);
+        let table = StateTable::<10>::new(width, height);
+        let states = all_states(width, height, 10);
+        let indices: HashSet<usize> = states
+            .iter()
+            .map(|state| table.index(state).unwrap())
+            .collect();
+        assert_eq!(indices.len(), states.len());
+        assert_eq!(indices.len(), table.traces.len());
+        assert!(indices.iter().all(|index| *index < table.traces.len()));
+    }
+
+    #[test]
+    fn test_state_table_bounds() {
+        let table = StateTable::<3>::new(2, 2);
+        let state = |x, y, num_straight_walks| State {
+            position: Coords::new(x, y),
+            direction: Direction::Right,
+            num_straight_walks,
+        };
+        assert!(table.index(&state(1, 1, 3)).is_some());
+        assert_eq!(table.index(&state(2, 0, 0)), None);
+        assert_eq!(table.index(&state(0, 2, 0)), None);
+        assert_eq!(table.index(&state(0, 0, 4)), None);
+        assert_eq!(table.get(&state(0, 0, 4)), None);
+    }
+
+    #[test]
+    fn test_leave_trace_per_state() {
+        let mut table = StateTable::<3>::new(2, 2);
+        let states = all_states(2, 2, 3);
+        for (trace, state) in states.iter().enumerate() {
+            assert_eq!(table.leave_trace(state, 100 + trace as u32), Ok(()));
+        }
+        for (trace, state) in states.iter().enumerate() {
+            assert_eq!(table.get(state), Some(100 + trace as u32));
+            assert_eq!(table.leave_trace(state, 100 + trace as u32), Err(()));
+            assert_eq!(table.leave_trace(state, trace as u32), Ok(()));
+        }
+    }
+}
@@ -1,61 +1,32 @@
-use crate::hamster::QuantumHamster;
 use crate::map::{Coords, Direction, Map};
-
-use std::collections::BinaryHeap;
+use crate::search::{search, State};
 
 pub fn run(input: &str) -> usize {
+    least_heat_loss(Map::parse(input))
+}
+
+pub fn least_heat_loss(map: Map<3>) -> usize {
     const MAX_STRAIGHT_WALKS: u8 = 3;
     const MIN_STRAIGHT_WALKS: u8 = 0;
 
-    hamster_wheel::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(input)
+    hamster_wheel::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map)
 }
 
 pub fn hamster_wheel<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>(
-    input: &str,
+    map: Map<MAX_STRAIGHT_WALKS>,
 ) -> usize {
-    let mut map = Map::parse(input);
     let starting_position = Coords::new(0, 0);
-
-    let hamster_down = QuantumHamster::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>::new(
-        starting_position,
-        Direction::Down,
-    );
-
-    let hamster_right = QuantumHamster::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>::new(
-        starting_position,
-        Direction::Right,
-    );
-
-    let mut hamsters = BinaryHeap::new();
-    hamsters.push(hamster_down);
-    hamsters.push(hamster_right);
-
-    while let Some(hamster) = hamsters.pop() {
-        let (alive_hamster, dead_hamster, zombie_hamster) = hamster.reorient();
-
-        if let Some(alive_hamster) = alive_hamster {
-            if let Some(alive_hamster) = alive_hamster.walk(&mut map) {
-                hamsters.push(alive_hamster);
-            }
-        }
-        if let Some(dead_hamster) = dead_hamster {
-            if let Some(dead_hamster) = dead_hamster.walk(&mut map) {
-                hamsters.push(dead_hamster);
-            }
-        }
-        if let Some(zombie_hamster) = zombie_hamster {
-            if let Some(zombie_hamster) = zombie_hamster.walk(&mut map) {
-                hamsters.push(zombie_hamster);
-            }
-        }
-    }
-
+    let starts = [
+        (starting_position, Direction::Down),
+        (starting_position, Direction::Right),
+    ];
     let target_position = Coords::new(map.width() - 1, map.height() - 1);
-    let overall_heat_loss = map
-        .get_field(target_position)
-        .unwrap()
-        .smallest_trace()
-        .unwrap();
+    let is_target = |state: &State| {
+        state.position == target_position && state.num_straight_walks >= MIN_STRAIGHT_WALKS
+    };
+
+    let result = search::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map, &starts, &[&is_target]);
+    let overall_heat_loss = result.goal_costs[0].unwrap();
 
     overall_heat_loss.try_into().unwrap()
 }
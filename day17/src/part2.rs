@@ -1,8 +1,30 @@
+use crate::map::Map;
 use crate::part1;
 
 pub fn run(input: &str) -> usize {
+    least_heat_loss(Map::parse(input))
+}
+
+pub fn least_heat_loss(map: Map<10>) -> usize {
     const MAX_STRAIGHT_WALKS: u8 = 10;
     const MIN_STRAIGHT_WALKS: u8 = 4;
 
-    part1::hamster_wheel::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(input)
+    part1::hamster_wheel::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unfortunate_example() {
+        // turning into the bottom right field is cheaper, but it is not allowed to stop there
+        let input = "\
+            111111111111\n\
+            999999999991\n\
+            999999999991\n\
+            999999999991\n\
+            999999999991";
+        assert_eq!(run(input), 71);
+    }
 }
@@ -0,0 +1,312 @@
+use crate::hamster::QuantumHamster;
+use crate::map::{Coords, Direction, Map};
+
+use std::collections::BinaryHeap;
+
+/// Where a hamster is, where it is heading and how often it walked straight in a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    pub position: Coords,
+    pub direction: Direction,
+    pub num_straight_walks: u8,
+}
+
+pub type Goal<'a> = &'a dyn Fn(&State) -> bool;
+
+/// Smallest heat loss to stop on each field in any state, `None` for fields where no hamster
+/// can stop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceField {
+    width: usize,
+    costs: Vec<Option<u32>>,
+}
+
+impl DistanceField {
+    /// `None` as well for coords outside of the map
+    pub fn get(&self, coords: Coords) -> Option<u32> {
+        if coords.x() >= self.width {
+            return None;
+        }
+        self.costs
+            .get(coords.y() * self.width + coords.x())
+            .copied()
+            .flatten()
+    }
+
+    fn set_if_unset(&mut self, coords: Coords, cost: u32) {
+        self.costs[coords.y() * self.width + coords.x()].get_or_insert(cost);
+    }
+}
+
+impl std::fmt::Display for DistanceField {
+    /// one row per line, with all costs right-aligned
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let max_cost = self.costs.iter().flatten().max().copied().unwrap_or(0);
+        let cell_width = max_cost.to_string().len();
+        for row in self.costs.chunks(self.width) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cost| match cost {
+                    Some(cost) => format!("{cost:>cell_width$}"),
+                    None => format!("{:>cell_width$}", "-"),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Counters of what happened to the hamsters during a search.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub pushes: usize,
+    pub pops: usize,
+    /// hamsters that did not walk on, as a hamster with less heat loss was there before
+    pub pruned: usize,
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} pushes, {} pops, {} pruned",
+            self.pushes, self.pops, self.pruned
+        )
+    }
+}
+
+/// The search right after a hamster left the heap.
+pub struct Snapshot<'a, const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8> {
+    pub stats: Stats,
+    /// heat loss of the hamster that just left the heap
+    pub heat_trace: u32,
+    pub map: &'a Map<MAX_STRAIGHT_WALKS>,
+    pub distances: &'a DistanceField,
+    hamsters: &'a BinaryHeap<QuantumHamster<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>>,
+}
+
+impl<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>
+    Snapshot<'_, MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>
+{
+    /// positions of all hamsters still waiting in the heap
+    pub fn frontier(&self) -> impl Iterator<Item = Coords> + '_ {
+        self.hamsters.iter().map(|hamster| hamster.state().position)
+    }
+}
+
+pub struct Search {
+    /// smallest heat loss for each goal, in the order they were given
+    pub goal_costs: Vec<Option<u32>>,
+    pub distances: DistanceField,
+    pub stats: Stats,
+}
+
+/// Lets hamsters loose from all `starts` at once, without heat loss on the starting fields.
+///
+/// Hamsters leave the heap ordered by their heat loss, so the first hamster that leaves it in a
+/// state matching a goal has found the cheapest way there.
+pub fn search<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>(
+    map: Map<MAX_STRAIGHT_WALKS>,
+    starts: &[(Coords, Direction)],
+    goals: &[Goal],
+) -> Search {
+    search_with::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map, starts, goals, |_| {})
+}
+
+/// [`search`], showing every hamster that leaves the heap to `on_pop`
+pub fn search_with<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>(
+    mut map: Map<MAX_STRAIGHT_WALKS>,
+    starts: &[(Coords, Direction)],
+    goals: &[Goal],
+    mut on_pop: impl FnMut(&Snapshot<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>),
+) -> Search {
+    let mut goal_costs = vec![None; goals.len()];
+    let mut distances = DistanceField {
+        width: map.width(),
+        costs: vec![None; map.width() * map.height()],
+    };
+    let mut stats = Stats {
+        pushes: starts.len(),
+        ..Stats::default()
+    };
+
+    let mut hamsters: BinaryHeap<_> = starts
+        .iter()
+        .map(|(position, direction)| {
+            QuantumHamster::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>::new(*position, *direction)
+        })
+        .collect();
+
+    while let Some(hamster) = hamsters.pop() {
+        stats.pops += 1;
+        let state = hamster.state();
+        let heat_trace = hamster.heat_trace();
+        // hamsters may only stop after walking straight long enough, or where they started
+        if state.num_straight_walks == 0 || state.num_straight_walks >= MIN_STRAIGHT_WALKS {
+            distances.set_if_unset(state.position, heat_trace);
+        }
+        for (goal, cost) in goals.iter().zip(goal_costs.iter_mut()) {
+            if cost.is_none() && goal(&state) {
+                *cost = Some(heat_trace);
+            }
+        }
+        on_pop(&Snapshot {
+            stats,
+            heat_trace,
+            map: &map,
+            distances: &distances,
+            hamsters: &hamsters,
+        });
+
+        let (alive_hamster, dead_hamster, zombie_hamster) = hamster.reorient();
+        for hamster in [alive_hamster, dead_hamster, zombie_hamster]
+            .into_iter()
+            .flatten()
+        {
+            let stays_on_map = hamster
+                .next_position()
+                .is_some_and(|position| map.get_field(position).is_some());
+            match hamster.walk(&mut map) {
+                Some(hamster) => {
+                    stats.pushes += 1;
+                    hamsters.push(hamster);
+                }
+                None if stays_on_map => stats.pruned += 1,
+                None => (),
+            }
+        }
+    }
+
+    Search {
+        goal_costs,
+        distances,
+        stats,
+    }
+}
+
+/// all fields on the left edge, heading into the map
+pub fn left_edge<const MAX_STRAIGHT_WALKS: u8>(
+    map: &Map<MAX_STRAIGHT_WALKS>,
+) -> Vec<(Coords, Direction)> {
+    (0..map.height())
+        .flat_map(|y| {
+            [Direction::Up, Direction::Down, Direction::Right]
+                .map(|direction| (Coords::new(0, y), direction))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        2413432311323\n\
+        3215453535623\n\
+        3255245654254\n\
+        3446585845452\n\
+        4546657867536\n\
+        1438598798454\n\
+        4457876987766\n\
+        3637877979653\n\
+        4654967986887\n\
+        4564679986453\n\
+        1224686865563\n\
+        2546548887735\n\
+        4322674655533";
+
+    fn top_left() -> Vec<(Coords, Direction)> {
+        vec![
+            (Coords::new(0, 0), Direction::Down),
+            (Coords::new(0, 0), Direction::Right),
+        ]
+    }
+
+    #[test]
+    fn test_goals() {
+        let map = Map::parse(EXAMPLE);
+        let bottom_right = |state: &State| state.position == Coords::new(12, 12);
+        let right_edge = |state: &State| state.position.x() == 12;
+        let nowhere = |state: &State| state.position.x() == 13;
+        let result = search::<0, 3>(map, &top_left(), &[&bottom_right, &right_edge, &nowhere]);
+        let right_edge_cost = (0..13)
+            .filter_map(|y| result.distances.get(Coords::new(12, y)))
+            .min();
+        assert_eq!(result.goal_costs, [Some(102), right_edge_cost, None]);
+        assert_eq!(result.distances.get(Coords::new(12, 12)), Some(102));
+        assert_eq!(result.distances.get(Coords::new(0, 0)), Some(0));
+    }
+
+    #[test]
+    fn test_min_straight_walks_goal() {
+        // the bottom right field is cheap to pass after turning into it, but stopping there is
+        // only allowed after walking straight long enough
+        let input = "\
+            111111111111\n\
+            999999999991\n\
+            999999999991\n\
+            999999999991\n\
+            999999999991";
+        let map = Map::parse(input);
+        let target = Coords::new(11, 4);
+        let anyhow = |state: &State| state.position == target;
+        let stopping = |state: &State| state.position == target && state.num_straight_walks >= 4;
+        let result = search::<4, 10>(map, &top_left(), &[&anyhow, &stopping]);
+        assert_eq!(result.goal_costs, [Some(47), Some(71)]);
+        assert_eq!(result.distances.get(target), Some(71));
+        assert_eq!(result.distances.get(Coords::new(0, 0)), Some(0));
+    }
+
+    #[test]
+    fn test_stats() {
+        let input = "\
+            19\n\
+            11";
+        let mut num_snapshots = 0;
+        let mut last_heat_trace = 0;
+        let result = search_with::<0, 3>(Map::parse(input), &top_left(), &[], |snapshot| {
+            num_snapshots += 1;
+            assert!(snapshot.heat_trace >= last_heat_trace);
+            last_heat_trace = snapshot.heat_trace;
+            assert_eq!(snapshot.stats.pops, num_snapshots);
+            assert_eq!(
+                snapshot.frontier().count(),
+                snapshot.stats.pushes - snapshot.stats.pops
+            );
+        });
+
+        let stats = result.stats;
+        assert_eq!(stats.pops, num_snapshots);
+        assert_eq!(stats.pushes, stats.pops);
+        // the hamster going down and right and the one going right and down meet at the end,
+        // and the more expensive one is pruned there
+        assert!(stats.pruned > 0);
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let input = "\
+            19\n\
+            11";
+        let result = search::<0, 3>(Map::parse(input), &top_left(), &[]);
+        assert_eq!(result.distances.to_string(), "0 9\n1 2\n");
+        assert_eq!(result.distances.get(Coords::new(1, 1)), Some(2));
+        assert_eq!(result.distances.get(Coords::new(2, 0)), None);
+        assert_eq!(result.distances.get(Coords::new(0, 2)), None);
+    }
+
+    #[test]
+    fn test_multiple_sources() {
+        let input = "\
+            1999\n\
+            9999\n\
+            1111";
+        let map = Map::parse(input);
+        let right_edge = |state: &State| state.position.x() == 3;
+        let starts = left_edge(&map);
+        let result = search::<0, 3>(map, &starts, &[&right_edge]);
+        assert_eq!(result.goal_costs, [Some(3)]);
+        assert_eq!(result.distances.get(Coords::new(0, 1)), Some(0));
+    }
+}
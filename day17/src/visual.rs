@@ -0,0 +1,148 @@
+use crate::map::{Coords, Direction, Map};
+use crate::search::{search_with, Search, Snapshot};
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::time::Duration;
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const FRONTIER_BACKGROUND: &str = "\x1b[43m";
+
+/// first and last shade of the 256 color grayscale ramp
+const DARKEST: u32 = 232;
+const BRIGHTEST: u32 = 255;
+
+/// background of explored fields, from dark for no heat loss to bright for `max_cost`
+fn shade(cost: u32, max_cost: u32) -> String {
+    let step = (cost * (BRIGHTEST - DARKEST))
+        .checked_div(max_cost)
+        .unwrap_or(0);
+    format!("\x1b[48;5;{}m", DARKEST + step)
+}
+
+/// Heat loss of every field, with explored fields shaded by their smallest known heat loss and
+/// fields with hamsters waiting in the heap highlighted. Unexplored fields are dimmed.
+pub fn ansi<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>(
+    snapshot: &Snapshot<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>,
+) -> String {
+    let frontier: HashSet<(usize, usize)> = snapshot
+        .frontier()
+        .map(|position| (position.x(), position.y()))
+        .collect();
+
+    let map = snapshot.map;
+    let mut out = String::new();
+    for y in 0..map.height() {
+        for x in 0..map.width() {
+            let coords = Coords::new(x, y);
+            let heat_loss = map.get_field(coords).unwrap().heat_loss();
+            let cell = if frontier.contains(&(x, y)) {
+                format!("{FRONTIER_BACKGROUND}{BOLD}{heat_loss}{RESET}")
+            } else if let Some(cost) = snapshot.distances.get(coords) {
+                let background = shade(cost, snapshot.heat_trace);
+                format!("{background}{heat_loss}{RESET}")
+            } else {
+                format!("{DIM}{heat_loss}{RESET}")
+            };
+            out.push_str(&cell);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn frame<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>(
+    snapshot: &Snapshot<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>,
+) -> String {
+    format!(
+        "{CLEAR}heat loss {}, {}\n{}",
+        snapshot.heat_trace,
+        snapshot.stats,
+        ansi(snapshot)
+    )
+}
+
+/// Searches from the top left like the puzzle, and redraws the map every `every` hamsters that
+/// left the heap, waiting `delay` after each frame. Whenever no hamster is waiting in the heap
+/// the map is redrawn as well, so the last hamster is always shown.
+pub fn watch<const MIN_STRAIGHT_WALKS: u8, const MAX_STRAIGHT_WALKS: u8>(
+    map: Map<MAX_STRAIGHT_WALKS>,
+    every: usize,
+    delay: Duration,
+    out: &mut impl Write,
+) -> io::Result<Search> {
+    let starts = [
+        (Coords::new(0, 0), Direction::Down),
+        (Coords::new(0, 0), Direction::Right),
+    ];
+
+    let mut result = Ok(());
+    let search =
+        search_with::<MIN_STRAIGHT_WALKS, MAX_STRAIGHT_WALKS>(map, &starts, &[], |snapshot| {
+            let heap_is_empty = snapshot.frontier().next().is_none();
+            if result.is_err() || (!snapshot.stats.pops.is_multiple_of(every) && !heap_is_empty) {
+                return;
+            }
+            result = write!(out, "{}", frame(snapshot)).and_then(|_| out.flush());
+            std::thread::sleep(delay);
+        });
+    result.map(|_| search)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shade() {
+        assert_eq!(shade(0, 0), "\x1b[48;5;232m");
+        assert_eq!(shade(0, 10), "\x1b[48;5;232m");
+        assert_eq!(shade(10, 10), "\x1b[48;5;255m");
+    }
+
+    #[test]
+    fn test_watch() {
+        let input = "\
+            19\n\
+            11";
+        let mut out = Vec::new();
+        let search = watch::<0, 3>(Map::parse(input), 2, Duration::ZERO, &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let num_frames = out.matches(CLEAR).count();
+        assert!(num_frames >= search.stats.pops / 2);
+        assert!(num_frames <= search.stats.pops);
+
+        // at the end, every field is explored and nothing is waiting anymore
+        let last_frame = out.rsplit(CLEAR).next().unwrap();
+        assert!(!last_frame.contains(FRONTIER_BACKGROUND));
+        assert!(!last_frame.contains(DIM));
+    }
+
+    #[test]
+    fn test_frontier_is_highlighted() {
+        let input = "\
+            19\n\
+            11";
+        let mut frames = Vec::new();
+        search_with::<0, 3>(
+            Map::parse(input),
+            &[(Coords::new(0, 0), Direction::Down)],
+            &[],
+            |snapshot| frames.push(ansi(snapshot)),
+        );
+
+        // the hamster that walked down just left the heap, while the one that turned right
+        // is still waiting
+        let highlighted = format!("{FRONTIER_BACKGROUND}{BOLD}9{RESET}");
+        let expected = format!(
+            "{}1{RESET}{highlighted}\n{}1{RESET}{DIM}1{RESET}\n",
+            shade(0, 1),
+            shade(1, 1)
+        );
+        assert_eq!(frames[1], expected);
+    }
+}
@@ -1,3 +1,5 @@
+use timings::Stage;
+
 #[derive(Default)]
 struct Game {
     id: u32,
@@ -110,11 +112,18 @@ fn read_input() -> String {
     fs::read_to_string(input_path).unwrap()
 }
 
+fn parse_games(input: &str) -> Vec<Game> {
+    input.lines().map(Game::parse).collect()
+}
+
 fn part1(input: &str) -> u32 {
+    sum_possible_ids(&parse_games(input))
+}
+
+fn sum_possible_ids(games: &[Game]) -> u32 {
     let max_colors = Colors::new(12, 13, 14);
-    input
-        .lines()
-        .map(Game::parse)
+    games
+        .iter()
         .filter_map(|game| {
             if !game.is_possible_with(max_colors) {
                 None
@@ -126,15 +135,27 @@ fn part1(input: &str) -> u32 {
 }
 
 fn part2(input: &str) -> u32 {
-    input
-        .lines()
-        .map(Game::parse)
-        .map(|game| game.color_power())
-        .sum()
+    sum_powers(&parse_games(input))
+}
+
+fn sum_powers(games: &[Game]) -> u32 {
+    games.iter().map(|game| game.color_power()).sum()
 }
 
 fn main() {
     let input = read_input();
+
+    // `day2 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || parse_games(&input)),
+        Stage::with_setup(
+            "part1",
+            || parse_games(&input),
+            |games| sum_possible_ids(&games),
+        ),
+        Stage::with_setup("part2", || parse_games(&input), |games| sum_powers(&games)),
+    ]);
+
     let result1 = part1(&input);
     let result2 = part2(&input);
     println!("Result1: {result1}");
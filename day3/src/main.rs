@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::iter;
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -241,7 +242,10 @@ impl NumCollector {
 }
 
 fn part1(input: &str) -> u32 {
-    let schematic = Schematic::parse(input);
+    sum_part_numbers(&Schematic::parse(input))
+}
+
+fn sum_part_numbers(schematic: &Schematic) -> u32 {
     let mut collector = NumCollector::default();
 
     let (len_x, len_y) = schematic.dimensions();
@@ -303,7 +307,10 @@ impl GearCollector {
 }
 
 fn part2(input: &str) -> u32 {
-    let schematic = Schematic::parse(input);
+    sum_gear_ratios(&Schematic::parse(input))
+}
+
+fn sum_gear_ratios(schematic: &Schematic) -> u32 {
     let mut collector = GearCollector::default();
 
     let (len_x, len_y) = schematic.dimensions();
@@ -331,6 +338,22 @@ fn part2(input: &str) -> u32 {
 
 fn main() {
     let input = read_input();
+
+    // `day3 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || Schematic::parse(&input)),
+        Stage::with_setup(
+            "part1",
+            || Schematic::parse(&input),
+            |schematic| sum_part_numbers(&schematic),
+        ),
+        Stage::with_setup(
+            "part2",
+            || Schematic::parse(&input),
+            |schematic| sum_gear_ratios(&schematic),
+        ),
+    ]);
+
     let result1 = part1(&input);
     let result2 = part2(&input);
     println!("Result1 is {result1}");
@@ -1,3 +1,5 @@
+use timings::Stage;
+
 pub fn read_input() -> String {
     use std::fs;
 
@@ -98,6 +100,10 @@ impl Pile {
         Self { cards }
     }
 
+    fn points(&self) -> u32 {
+        self.cards.iter().map(|card| card.points()).sum()
+    }
+
     fn pop(&mut self) -> Option<Card> {
         self.cards.pop()
     }
@@ -118,15 +124,14 @@ impl Pile {
 }
 
 fn part1(input: &str) -> u32 {
-    input
-        .lines()
-        .map(Card::parse)
-        .map(|card| card.points())
-        .sum()
+    Pile::new(input).points()
 }
 
 fn part2(input: &str) -> u32 {
-    let mut pile = Pile::new(&input);
+    count_cards(Pile::new(&input))
+}
+
+fn count_cards(mut pile: Pile) -> u32 {
     let mut num_cards = 0;
 
     while let Some(card) = pile.pop() {
@@ -139,6 +144,14 @@ fn part2(input: &str) -> u32 {
 
 fn main() {
     let input = read_input();
+
+    // `day4 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || Pile::new(&input)),
+        Stage::with_setup("part1", || Pile::new(&input), |pile| pile.points()),
+        Stage::with_setup("part2", || Pile::new(&input), count_cards),
+    ]);
+
     let result1 = part1(&input);
     let result2 = part2(&input);
     println!("Result1 is {result1}");
@@ -4,6 +4,7 @@ use items::UncannyItem;
 
 use std::marker::PhantomData;
 use std::ops::Range;
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -147,59 +148,74 @@ impl Iterator for SeedIterator {
     }
 }
 
-fn part1(input: &str) -> usize {
-    let seed_to_soil: UncannyMap<items::Seed, items::Soil> = UncannyMap::parse(input);
-    let soil_to_fert: UncannyMap<items::Soil, items::Fertilizer> = UncannyMap::parse(input);
-    let fert_to_water: UncannyMap<items::Fertilizer, items::Water> = UncannyMap::parse(input);
-    let water_to_light: UncannyMap<items::Water, items::Light> = UncannyMap::parse(input);
-    let light_to_temp: UncannyMap<items::Light, items::Temperature> = UncannyMap::parse(input);
-    let temp_to_humidity: UncannyMap<items::Temperature, items::Humidity> =
-        UncannyMap::parse(input);
-    let humidity_to_location: UncannyMap<items::Humidity, items::Location> =
-        UncannyMap::parse(input);
+/// all maps from a seed to its location
+struct Almanac {
+    seed_to_soil: UncannyMap<items::Seed, items::Soil>,
+    soil_to_fert: UncannyMap<items::Soil, items::Fertilizer>,
+    fert_to_water: UncannyMap<items::Fertilizer, items::Water>,
+    water_to_light: UncannyMap<items::Water, items::Light>,
+    light_to_temp: UncannyMap<items::Light, items::Temperature>,
+    temp_to_humidity: UncannyMap<items::Temperature, items::Humidity>,
+    humidity_to_location: UncannyMap<items::Humidity, items::Location>,
+}
 
-    let seeds = parse_seeds_part1(input);
+impl Almanac {
+    fn parse(input: &str) -> Self {
+        Self {
+            seed_to_soil: UncannyMap::parse(input),
+            soil_to_fert: UncannyMap::parse(input),
+            fert_to_water: UncannyMap::parse(input),
+            water_to_light: UncannyMap::parse(input),
+            light_to_temp: UncannyMap::parse(input),
+            temp_to_humidity: UncannyMap::parse(input),
+            humidity_to_location: UncannyMap::parse(input),
+        }
+    }
 
-    seeds
-        .iter()
-        .map(|seed| seed_to_soil.get(*seed))
-        .map(|soil| soil_to_fert.get(soil))
-        .map(|fert| fert_to_water.get(fert))
-        .map(|water| water_to_light.get(water))
-        .map(|light| light_to_temp.get(light))
-        .map(|temp| temp_to_humidity.get(temp))
-        .map(|humidity| humidity_to_location.get(humidity))
-        .map(|location| location.value())
-        .min()
-        .unwrap()
+    fn lowest_location(&self, seeds: impl Iterator<Item = items::Seed>) -> usize {
+        seeds
+            .map(|seed| self.seed_to_soil.get(seed))
+            .map(|soil| self.soil_to_fert.get(soil))
+            .map(|fert| self.fert_to_water.get(fert))
+            .map(|water| self.water_to_light.get(water))
+            .map(|light| self.light_to_temp.get(light))
+            .map(|temp| self.temp_to_humidity.get(temp))
+            .map(|humidity| self.humidity_to_location.get(humidity))
+            .map(|location| location.value())
+            .min()
+            .unwrap()
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let seeds = parse_seeds_part1(input);
+    Almanac::parse(input).lowest_location(seeds.into_iter())
 }
 
 fn part2(input: &str) -> usize {
-    let seed_to_soil: UncannyMap<items::Seed, items::Soil> = UncannyMap::parse(input);
-    let soil_to_fert: UncannyMap<items::Soil, items::Fertilizer> = UncannyMap::parse(input);
-    let fert_to_water: UncannyMap<items::Fertilizer, items::Water> = UncannyMap::parse(input);
-    let water_to_light: UncannyMap<items::Water, items::Light> = UncannyMap::parse(input);
-    let light_to_temp: UncannyMap<items::Light, items::Temperature> = UncannyMap::parse(input);
-    let temp_to_humidity: UncannyMap<items::Temperature, items::Humidity> =
-        UncannyMap::parse(input);
-    let humidity_to_location: UncannyMap<items::Humidity, items::Location> =
-        UncannyMap::parse(input);
-
-    SeedIterator::parse(input)
-        .map(|seed| seed_to_soil.get(seed))
-        .map(|soil| soil_to_fert.get(soil))
-        .map(|fert| fert_to_water.get(fert))
-        .map(|water| water_to_light.get(water))
-        .map(|light| light_to_temp.get(light))
-        .map(|temp| temp_to_humidity.get(temp))
-        .map(|humidity| humidity_to_location.get(humidity))
-        .map(|location| location.value())
-        .min()
-        .unwrap()
+    Almanac::parse(input).lowest_location(SeedIterator::parse(input))
 }
 
 fn main() {
     let input = read_input();
+
+    // `day5 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || {
+            (parse_seeds_part1(&input), Almanac::parse(&input))
+        }),
+        Stage::with_setup(
+            "part1",
+            || (parse_seeds_part1(&input), Almanac::parse(&input)),
+            |(seeds, almanac)| almanac.lowest_location(seeds.into_iter()),
+        ),
+        Stage::with_setup(
+            "part2",
+            || (SeedIterator::parse(&input), Almanac::parse(&input)),
+            |(seeds, almanac)| almanac.lowest_location(seeds),
+        ),
+    ]);
+
     let result1 = part1(&input);
     let result2 = part2(&input);
     println!("Result1 is {result1}");
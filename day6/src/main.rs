@@ -1,3 +1,5 @@
+use timings::Stage;
+
 pub fn read_input() -> String {
     use std::fs;
 
@@ -54,9 +56,11 @@ fn parse_races(input: &str) -> impl Iterator<Item = Race> + '_ {
 }
 
 fn part1(input: &str) -> usize {
-    parse_races(input)
-        .map(|race| race.calc_num_win_options())
-        .product()
+    multiply_win_options(parse_races(input))
+}
+
+fn multiply_win_options(races: impl Iterator<Item = Race>) -> usize {
+    races.map(|race| race.calc_num_win_options()).product()
 }
 
 fn parse_badly_kerned_race(input: &str) -> Race {
@@ -83,6 +87,25 @@ fn part2(input: &str) -> usize {
 
 fn main() {
     let input = read_input();
+
+    // `day6 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || {
+            let races: Vec<_> = parse_races(&input).collect();
+            (races, parse_badly_kerned_race(&input))
+        }),
+        Stage::with_setup(
+            "part1",
+            || parse_races(&input).collect::<Vec<_>>(),
+            |races| multiply_win_options(races.into_iter()),
+        ),
+        Stage::with_setup(
+            "part2",
+            || parse_badly_kerned_race(&input),
+            |race| race.calc_num_win_options(),
+        ),
+    ]);
+
     let result1 = part1(&input);
     let result2 = part2(&input);
     println!("Result1 is {result1}");
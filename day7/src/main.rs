@@ -1,5 +1,9 @@
 mod part1;
 mod part2;
+mod report;
+
+use report::Format;
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -10,6 +14,40 @@ pub fn read_input() -> String {
 
 fn main() {
     let input = read_input();
+
+    // `day7 report <1|2> [text|csv|json]` explains the ranking instead
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some("report") = args.first().map(String::as_str) {
+        let format: Format = args
+            .get(2)
+            .map(|format| format.parse().unwrap())
+            .unwrap_or(Format::Text);
+        let report = match args.get(1).map(String::as_str) {
+            Some("1") => part1::report(&input),
+            Some("2") | None => part2::report(&input),
+            Some(part) => panic!("unknown part '{part}'"),
+        };
+        print!("{}", report.render(format));
+        return;
+    }
+
+    // `day7 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("parse", || {
+            (part1::parse_hands(&input), part2::parse_hands(&input))
+        }),
+        Stage::with_setup(
+            "part1",
+            || part1::parse_hands(&input),
+            part1::total_winnings,
+        ),
+        Stage::with_setup(
+            "part2",
+            || part2::parse_hands(&input),
+            part2::total_winnings,
+        ),
+    ]);
+
     let result1 = part1::part1(&input);
     let result2 = part2::part2(&input);
     println!("Result1 is {result1}");
@@ -1,5 +1,14 @@
+use crate::report::{HandReport, Report};
+
 pub fn part1(input: &str) -> usize {
-    let mut hands: Vec<Hand> = input.lines().map(Hand::parse).collect();
+    total_winnings(parse_hands(input))
+}
+
+pub fn parse_hands(input: &str) -> Vec<Hand> {
+    input.lines().map(Hand::parse).collect()
+}
+
+pub fn total_winnings(mut hands: Vec<Hand>) -> usize {
     hands.sort_unstable();
     hands
         .iter()
@@ -8,6 +17,17 @@ pub fn part1(input: &str) -> usize {
         .sum()
 }
 
+pub fn report(input: &str) -> Report {
+    let mut hands: Vec<Hand> = input.lines().map(Hand::parse).collect();
+    hands.sort_unstable();
+    let hands = hands
+        .iter()
+        .enumerate()
+        .map(|(index, hand)| hand.report(index + 1))
+        .collect();
+    Report::new(hands)
+}
+
 #[derive(Debug, Clone, Copy)]
 enum HandType {
     FiveOfAKind,
@@ -48,6 +68,17 @@ impl HandType {
             _ => panic!("unexpected largest counts: {largest_count}, {second_largest_count}"),
         }
     }
+    fn name(&self) -> &'static str {
+        match self {
+            Self::FiveOfAKind => "FiveOfAKind",
+            Self::FourOfAKind => "FourOfAKind",
+            Self::FullHouse => "FullHouse",
+            Self::ThreeOfAKind => "ThreeOfAKind",
+            Self::TwoPair => "TwoPair",
+            Self::OnePair => "OnePair",
+            Self::HighCard => "HighCard",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -73,8 +104,17 @@ impl From<char> for Card {
         Self(int_repr)
     }
 }
+impl Card {
+    fn symbol(&self) -> char {
+        CARD_SYMBOLS[self.0]
+    }
+}
 const NUM_DIFFERENT_CARDS: usize = 13;
+const CARD_SYMBOLS: [char; NUM_DIFFERENT_CARDS] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
 
+#[derive(Debug)]
 struct Cards([Card; 5]);
 impl Cards {
     fn parse(cards_str: &str) -> Self {
@@ -108,9 +148,18 @@ impl Cards {
         self.type_ranking() + self.highcard_ranking()
     }
 }
+impl std::fmt::Display for Cards {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for card in &self.0 {
+            write!(f, "{}", card.symbol())?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
-struct Hand {
+pub struct Hand {
+    cards: Cards,
     ranking: usize,
     bid: usize,
 }
@@ -121,11 +170,25 @@ impl Hand {
         let ranking = cards.ranking();
         let bid = bid.parse().unwrap();
 
-        Self { ranking, bid }
+        Self {
+            cards,
+            ranking,
+            bid,
+        }
     }
     fn winning(&self, rank: usize) -> usize {
         self.bid * rank
     }
+    fn report(&self, rank: usize) -> HandReport {
+        HandReport {
+            cards: self.cards.to_string(),
+            best_form: self.cards.to_string(),
+            hand_type: self.cards.hand_type().name(),
+            rank,
+            bid: self.bid,
+            winnings: self.winning(rank),
+        }
+    }
 }
 impl PartialEq for Hand {
     fn eq(&self, other: &Self) -> bool {
@@ -135,7 +198,7 @@ impl PartialEq for Hand {
 impl Eq for Hand {}
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.ranking.partial_cmp(&other.ranking)
+        Some(self.cmp(other))
     }
 }
 impl Ord for Hand {
@@ -1,5 +1,14 @@
+use crate::report::{HandReport, Report};
+
 pub fn part2(input: &str) -> usize {
-    let mut hands: Vec<Hand> = input.lines().map(Hand::parse).collect();
+    total_winnings(parse_hands(input))
+}
+
+pub fn parse_hands(input: &str) -> Vec<Hand> {
+    input.lines().map(Hand::parse).collect()
+}
+
+pub fn total_winnings(mut hands: Vec<Hand>) -> usize {
     hands.sort_unstable();
     hands
         .iter()
@@ -8,6 +17,17 @@ pub fn part2(input: &str) -> usize {
         .sum()
 }
 
+pub fn report(input: &str) -> Report {
+    let mut hands: Vec<Hand> = input.lines().map(Hand::parse).collect();
+    hands.sort_unstable();
+    let hands = hands
+        .iter()
+        .enumerate()
+        .map(|(index, hand)| hand.report(index + 1))
+        .collect();
+    Report::new(hands)
+}
+
 #[derive(Debug, Clone, Copy)]
 enum HandType {
     FiveOfAKind,
@@ -28,7 +48,7 @@ impl HandType {
 
         let mut largest_count = 0;
         let mut second_largest_count = 0;
-        for (index, count) in &mut counts.iter().enumerate().skip(1) {
+        for count in counts.iter().skip(1) {
             if *count > largest_count {
                 second_largest_count = largest_count;
                 largest_count = *count;
@@ -51,6 +71,17 @@ impl HandType {
             _ => panic!("unexpected largest counts: {largest_count}, {second_largest_count}"),
         }
     }
+    fn name(&self) -> &'static str {
+        match self {
+            Self::FiveOfAKind => "FiveOfAKind",
+            Self::FourOfAKind => "FourOfAKind",
+            Self::FullHouse => "FullHouse",
+            Self::ThreeOfAKind => "ThreeOfAKind",
+            Self::TwoPair => "TwoPair",
+            Self::OnePair => "OnePair",
+            Self::HighCard => "HighCard",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -76,8 +107,18 @@ impl From<char> for Card {
         Self(int_repr)
     }
 }
+impl Card {
+    fn symbol(&self) -> char {
+        CARD_SYMBOLS[self.0]
+    }
+}
 const NUM_DIFFERENT_CARDS: usize = 13;
+const JOKER: usize = 0;
+const CARD_SYMBOLS: [char; NUM_DIFFERENT_CARDS] = [
+    'J', '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'Q', 'K', 'A',
+];
 
+#[derive(Debug)]
 struct Cards([Card; 5]);
 impl Cards {
     fn parse(cards_str: &str) -> Self {
@@ -110,10 +151,35 @@ impl Cards {
     fn ranking(&self) -> usize {
         self.type_ranking() + self.highcard_ranking()
     }
+    fn best_form(&self) -> Self {
+        let mut counts = [0_usize; NUM_DIFFERENT_CARDS];
+        for card in &self.0 {
+            counts[card.0] += 1;
+        }
+
+        // jokers become the most common other card, the stronger one on ties
+        let substitute = (1..NUM_DIFFERENT_CARDS)
+            .max_by_key(|index| counts[*index])
+            .unwrap();
+        let cards = self.0.map(|card| match card.0 {
+            JOKER => Card(substitute),
+            _ => card,
+        });
+        Self(cards)
+    }
+}
+impl std::fmt::Display for Cards {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for card in &self.0 {
+            write!(f, "{}", card.symbol())?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
-struct Hand {
+pub struct Hand {
+    cards: Cards,
     ranking: usize,
     bid: usize,
 }
@@ -124,11 +190,25 @@ impl Hand {
         let ranking = cards.ranking();
         let bid = bid.parse().unwrap();
 
-        Self { ranking, bid }
+        Self {
+            cards,
+            ranking,
+            bid,
+        }
     }
     fn winning(&self, rank: usize) -> usize {
         self.bid * rank
     }
+    fn report(&self, rank: usize) -> HandReport {
+        HandReport {
+            cards: self.cards.to_string(),
+            best_form: self.cards.best_form().to_string(),
+            hand_type: self.cards.hand_type().name(),
+            rank,
+            bid: self.bid,
+            winnings: self.winning(rank),
+        }
+    }
 }
 impl PartialEq for Hand {
     fn eq(&self, other: &Self) -> bool {
@@ -138,7 +218,7 @@ impl PartialEq for Hand {
 impl Eq for Hand {}
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.ranking.partial_cmp(&other.ranking)
+        Some(self.cmp(other))
     }
 }
 impl Ord for Hand {
@@ -171,6 +251,13 @@ mod tests {
         assert!(cards.ranking() < other_cards.ranking());
     }
 
+    #[test]
+    fn test_best_form() {
+        assert_eq!(Cards::parse("KTJJT").best_form().to_string(), "KTTTT");
+        assert_eq!(Cards::parse("JJJJJ").best_form().to_string(), "AAAAA");
+        assert_eq!(Cards::parse("2JK3Q").best_form().to_string(), "2KK3Q");
+    }
+
     #[test]
     fn test_example_part2() {
         let input = "\
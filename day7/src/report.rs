@@ -0,0 +1,246 @@
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Csv,
+    Json,
+}
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown report format '{s}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandReport {
+    pub cards: String,
+    pub best_form: String,
+    pub hand_type: &'static str,
+    pub rank: usize,
+    pub bid: usize,
+    pub winnings: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeStats {
+    pub hand_type: &'static str,
+    pub count: usize,
+    pub total_bid: usize,
+    pub total_winnings: usize,
+}
+
+pub struct Report {
+    pub hands: Vec<HandReport>,
+}
+impl Report {
+    /// expects the hands to be sorted by rank
+    pub fn new(hands: Vec<HandReport>) -> Self {
+        Self { hands }
+    }
+
+    pub fn total_winnings(&self) -> usize {
+        self.hands.iter().map(|hand| hand.winnings).sum()
+    }
+
+    /// from weakest to strongest type, as hands of one type always rank next to each other
+    pub fn type_stats(&self) -> Vec<TypeStats> {
+        let mut stats: Vec<TypeStats> = Vec::new();
+        for hand in &self.hands {
+            match stats.last_mut() {
+                Some(last) if last.hand_type == hand.hand_type => {
+                    last.count += 1;
+                    last.total_bid += hand.bid;
+                    last.total_winnings += hand.winnings;
+                }
+                _ => stats.push(TypeStats {
+                    hand_type: hand.hand_type,
+                    count: 1,
+                    total_bid: hand.bid,
+                    total_winnings: hand.winnings,
+                }),
+            }
+        }
+        stats
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.to_text(),
+            Format::Csv => self.to_csv(),
+            Format::Json => self.to_json(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{:>5}  {:<5}  {:<5}  {:<12}  {:>5}  {:>9}",
+            "rank", "cards", "best", "type", "bid", "winnings"
+        )
+        .unwrap();
+        for hand in &self.hands {
+            writeln!(
+                out,
+                "{:>5}  {:<5}  {:<5}  {:<12}  {:>5}  {:>9}",
+                hand.rank, hand.cards, hand.best_form, hand.hand_type, hand.bid, hand.winnings
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "{:<12}  {:>5}  {:>9}  {:>9}",
+            "type", "count", "bids", "winnings"
+        )
+        .unwrap();
+        for stats in self.type_stats() {
+            writeln!(
+                out,
+                "{:<12}  {:>5}  {:>9}  {:>9}",
+                stats.hand_type, stats.count, stats.total_bid, stats.total_winnings
+            )
+            .unwrap();
+        }
+        writeln!(out, "total winnings: {}", self.total_winnings()).unwrap();
+        out
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("rank,cards,best_form,hand_type,bid,winnings\n");
+        for hand in &self.hands {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                hand.rank, hand.cards, hand.best_form, hand.hand_type, hand.bid, hand.winnings
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let hands: Vec<String> = self
+            .hands
+            .iter()
+            .map(|hand| {
+                format!(
+                    "{{\"rank\":{},\"cards\":\"{}\",\"best_form\":\"{}\",\"hand_type\":\"{}\",\"bid\":{},\"winnings\":{}}}",
+                    hand.rank, hand.cards, hand.best_form, hand.hand_type, hand.bid, hand.winnings
+                )
+            })
+            .collect();
+        let stats: Vec<String> = self
+            .type_stats()
+            .iter()
+            .map(|stats| {
+                format!(
+                    "{{\"hand_type\":\"{}\",\"count\":{},\"total_bid\":{},\"total_winnings\":{}}}",
+                    stats.hand_type, stats.count, stats.total_bid, stats.total_winnings
+                )
+            })
+            .collect();
+        format!(
+            "{{\"total_winnings\":{},\"hands\":[{}],\"type_stats\":[{}]}}\n",
+            self.total_winnings(),
+            hands.join(","),
+            stats.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        32T3K 765\n\
+        T55J5 684\n\
+        KK677 28\n\
+        KTJJT 220\n\
+        QQQJA 483";
+
+    #[test]
+    fn test_report_part1() {
+        let report = crate::part1::report(EXAMPLE);
+        assert_eq!(report.total_winnings(), 6440);
+
+        let hands = &report.hands;
+        assert_eq!(hands[0].cards, "32T3K");
+        assert_eq!(hands[0].hand_type, "OnePair");
+        assert_eq!(hands[4].cards, "QQQJA");
+        assert_eq!(hands[4].best_form, "QQQJA");
+        assert_eq!(hands[4].rank, 5);
+        assert_eq!(hands[4].winnings, 5 * 483);
+    }
+
+    #[test]
+    fn test_report_part2() {
+        let report = crate::part2::report(EXAMPLE);
+        assert_eq!(report.total_winnings(), 5905);
+
+        let hands = &report.hands;
+        assert_eq!(hands[4].cards, "KTJJT");
+        assert_eq!(hands[4].best_form, "KTTTT");
+        assert_eq!(hands[4].hand_type, "FourOfAKind");
+        assert_eq!(hands[3].cards, "QQQJA");
+        assert_eq!(hands[3].best_form, "QQQQA");
+    }
+
+    #[test]
+    fn test_type_stats() {
+        let stats = crate::part2::report(EXAMPLE).type_stats();
+        let expected = [
+            TypeStats {
+                hand_type: "OnePair",
+                count: 1,
+                total_bid: 765,
+                total_winnings: 765,
+            },
+            TypeStats {
+                hand_type: "TwoPair",
+                count: 1,
+                total_bid: 28,
+                total_winnings: 56,
+            },
+            TypeStats {
+                hand_type: "FourOfAKind",
+                count: 3,
+                total_bid: 684 + 483 + 220,
+                total_winnings: 684 * 3 + 483 * 4 + 220 * 5,
+            },
+        ];
+        assert_eq!(stats, expected);
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = crate::part1::report(EXAMPLE).to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("rank,cards,best_form,hand_type,bid,winnings")
+        );
+        assert_eq!(lines.next(), Some("1,32T3K,32T3K,OnePair,765,765"));
+        assert_eq!(lines.count(), 4);
+    }
+
+    #[test]
+    fn test_json() {
+        let json = crate::part2::report("JJJJJ 3").to_json();
+        assert_eq!(
+            json,
+            "{\"total_winnings\":3,\
+            \"hands\":[{\"rank\":1,\"cards\":\"JJJJJ\",\"best_form\":\"AAAAA\",\"hand_type\":\"FiveOfAKind\",\"bid\":3,\"winnings\":3}],\
+            \"type_stats\":[{\"hand_type\":\"FiveOfAKind\",\"count\":1,\"total_bid\":3,\"total_winnings\":3}]}\n"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use crate::part1::{Direction, Guide, NodeArena};
+use crate::part2::ghost_cycles;
+
+use std::fmt::Write;
+
+const LOOP_COLORS: [&str; 6] = ["blue", "orange", "purple", "darkgreen", "brown", "magenta"];
+
+/// Graphviz rendering of the network: start nodes are green, end nodes red and the loop of
+/// every ghost gets its own edge color.
+pub fn export(input: &str) -> String {
+    let nodes = NodeArena::parse(input);
+    let guide = Guide::parse(input);
+    let loops: Vec<_> = ghost_cycles(&nodes, &guide)
+        .iter()
+        .map(|cycle| cycle.loop_edges(&nodes, guide.clone()))
+        .collect();
+
+    let mut out = String::from("digraph network {\n");
+    for node_id in nodes.node_ids() {
+        let style = if nodes.is_start(node_id) {
+            " [style=filled, fillcolor=palegreen]"
+        } else if nodes.is_end(node_id) {
+            " [style=filled, fillcolor=salmon]"
+        } else {
+            ""
+        };
+        writeln!(out, "    \"{}\"{style};", nodes.name(node_id)).unwrap();
+    }
+
+    for node_id in nodes.node_ids() {
+        for direction in [Direction::Left, Direction::Right] {
+            let from = nodes.name(node_id);
+            let to = nodes.name(nodes.walk_further(node_id, direction));
+            let label = match direction {
+                Direction::Left => 'L',
+                Direction::Right => 'R',
+            };
+            let colors: Vec<&str> = loops
+                .iter()
+                .enumerate()
+                .filter(|(_, edges)| edges.contains(&(node_id, direction)))
+                .map(|(index, _)| LOOP_COLORS[index % LOOP_COLORS.len()])
+                .collect();
+
+            if colors.is_empty() {
+                writeln!(out, "    \"{from}\" -> \"{to}\" [label=\"{label}\"];").unwrap();
+            } else {
+                let colors = colors.join(":");
+                writeln!(
+                    out,
+                    "    \"{from}\" -> \"{to}\" [label=\"{label}\", color=\"{colors}\", penwidth=2];"
+                )
+                .unwrap();
+            }
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let input = "\
+            L\n\
+            \n\
+            11A = (11Z, 11Z)\n\
+            11Z = (11A, 11A)";
+        let expected = "\
+            digraph network {\n    \
+                \"11A\" [style=filled, fillcolor=palegreen];\n    \
+                \"11Z\" [style=filled, fillcolor=salmon];\n    \
+                \"11A\" -> \"11Z\" [label=\"L\", color=\"blue\", penwidth=2];\n    \
+                \"11A\" -> \"11Z\" [label=\"R\"];\n    \
+                \"11Z\" -> \"11A\" [label=\"L\", color=\"blue\", penwidth=2];\n    \
+                \"11Z\" -> \"11A\" [label=\"R\"];\n\
+            }\n";
+        assert_eq!(export(input), expected);
+    }
+}
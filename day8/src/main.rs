@@ -1,6 +1,9 @@
+mod dot;
 mod part1;
 mod part2;
 
+use timings::Stage;
+
 pub fn read_input() -> String {
     use std::fs;
 
@@ -10,6 +13,25 @@ pub fn read_input() -> String {
 
 fn main() {
     let input = read_input();
+
+    // `day8 dot | dot -Tsvg > network.svg` shows the structure of the input
+    if std::env::args().nth(1).as_deref() == Some("dot") {
+        print!("{}", dot::export(&input));
+        return;
+    }
+
+    // `day8 timings [warmup] [samples]` times parsing and both parts for the bench crate
+    let parse = || (part1::NodeArena::parse(&input), part1::Guide::parse(&input));
+    timings::run_from_args(&mut [
+        Stage::new("parse", parse),
+        Stage::with_setup("part1", parse, |(nodes, guide)| {
+            part1::walk_to_end(&nodes, guide)
+        }),
+        Stage::with_setup("part2", parse, |(nodes, guide)| {
+            part2::walk_all_ghosts(&nodes, &guide)
+        }),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
@@ -1,44 +1,48 @@
 use std::collections::HashMap;
 
 pub fn run(input: &str) -> usize {
-    let nodes = NodeArena::parse(input);
-    let mut guide = Guide::parse(input);
-    let start_id = NodeId::from("AAA");
-    let end_id = NodeId::from("ZZZ");
+    walk_to_end(&NodeArena::parse(input), Guide::parse(input))
+}
+
+pub fn walk_to_end(nodes: &NodeArena, mut guide: Guide) -> usize {
+    let start_id = nodes.id("AAA");
+    let end_id = nodes.id("ZZZ");
 
     let mut current_node_id = start_id;
     let mut num_walks = 0;
 
     while current_node_id != end_id {
-        let node = nodes.get(current_node_id);
-        current_node_id = node.walk_further(guide.where_to());
+        current_node_id = nodes.walk_further(current_node_id, guide.where_to());
         num_walks += 1;
     }
     num_walks
 }
 
+#[derive(Clone)]
 pub struct Guide<'a> {
     data: &'a str,
-    iter: std::str::Chars<'a>,
+    position: usize,
 }
 impl<'a> Guide<'a> {
     pub fn parse(input: &'a str) -> Self {
         let data = input.lines().next().unwrap();
-        let iter = data.chars();
-        Self { data, iter }
+        Self { data, position: 0 }
     }
     fn get_next_char(&mut self) -> char {
-        self.iter.next().unwrap_or_else(|| {
-            self.iter = self.data.chars();
-            return self.iter.next().unwrap();
-        })
+        let next_char = self.data.as_bytes()[self.position] as char;
+        self.position = (self.position + 1) % self.data.len();
+        next_char
+    }
+    /// index of the instruction that will be followed next
+    pub fn position(&self) -> usize {
+        self.position
     }
     pub fn where_to(&mut self) -> Direction {
         self.get_next_char().into()
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Direction {
     Left,
     Right,
@@ -53,71 +57,116 @@ impl From<char> for Direction {
     }
 }
 
+/// dense index into the adjacency arrays of a `NodeArena`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct NodeId<'a>(&'a str);
-impl<'a> From<&'a str> for NodeId<'a> {
-    fn from(value: &'a str) -> Self {
-        Self(value)
-    }
-}
-impl<'a> NodeId<'a> {
-    pub fn is_start(&self) -> bool {
-        self.0.ends_with("A")
-    }
-    pub fn is_end(&self) -> bool {
-        self.0.ends_with("Z")
-    }
-}
+pub struct NodeId(usize);
 
 pub struct NodeArena<'a> {
-    nodes: HashMap<NodeId<'a>, Node<'a>>,
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, NodeId>,
+    left: Vec<NodeId>,
+    right: Vec<NodeId>,
 }
 impl<'a> NodeArena<'a> {
     pub fn parse(input: &'a str) -> Self {
-        let mut nodes = HashMap::new();
-        'line_loop: for line in input.lines() {
-            let Some((node_id, node_content)) = line.split_once(" = ") else {
-                continue 'line_loop;
-            };
-
-            let node_id = NodeId::from(node_id);
-            let node = Node::parse(node_content);
-            let overwritten = nodes.insert(node_id, node);
+        let lines: Vec<(&str, &str)> = input
+            .lines()
+            .filter_map(|line| line.split_once(" = "))
+            .collect();
+
+        // intern all names first, so neighbours can refer to nodes that are defined later
+        let mut names = Vec::with_capacity(lines.len());
+        let mut ids = HashMap::with_capacity(lines.len());
+        for (name, _) in &lines {
+            let overwritten = ids.insert(*name, NodeId(names.len()));
             assert!(overwritten.is_none());
+            names.push(*name);
         }
-        Self { nodes }
-    }
 
-    pub fn get(&self, node_id: NodeId<'a>) -> &Node {
-        self.nodes.get(&node_id).unwrap()
-    }
+        let mut left = Vec::with_capacity(lines.len());
+        let mut right = Vec::with_capacity(lines.len());
+        for (_, content) in &lines {
+            let (left_name, right_name) = Self::parse_neighbours(content);
+            left.push(ids[left_name]);
+            right.push(ids[right_name]);
+        }
 
-    pub fn inner(&self) -> &HashMap<NodeId<'a>, Node<'a>> {
-        &self.nodes
+        Self {
+            names,
+            ids,
+            left,
+            right,
+        }
     }
-}
 
-pub struct Node<'a> {
-    left: NodeId<'a>,
-    right: NodeId<'a>,
-}
-impl<'a> Node<'a> {
-    fn parse(content: &'a str) -> Self {
+    fn parse_neighbours(content: &str) -> (&str, &str) {
         // remove round brackets
         let mut chars = content.chars();
         chars.next();
         chars.next_back();
         let content = chars.as_str();
-        let (left, right) = content.split_once(", ").unwrap();
-        Self {
-            left: left.into(),
-            right: right.into(),
-        }
+        content.split_once(", ").unwrap()
     }
-    pub fn walk_further(&self, direction: Direction) -> NodeId<'a> {
+
+    pub fn id(&self, name: &str) -> NodeId {
+        self.ids[name]
+    }
+
+    pub fn name(&self, node_id: NodeId) -> &'a str {
+        self.names[node_id.0]
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.names.len()).map(NodeId)
+    }
+
+    pub fn is_start(&self, node_id: NodeId) -> bool {
+        self.name(node_id).ends_with('A')
+    }
+
+    pub fn is_end(&self, node_id: NodeId) -> bool {
+        self.name(node_id).ends_with('Z')
+    }
+
+    pub fn walk_further(&self, node_id: NodeId, direction: Direction) -> NodeId {
         match direction {
-            Direction::Left => self.left,
-            Direction::Right => self.right,
+            Direction::Left => self.left[node_id.0],
+            Direction::Right => self.right[node_id.0],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning() {
+        let input = "\
+            LLR\n\
+            \n\
+            AAA = (BBB, BBB)\n\
+            BBB = (AAA, ZZZ)\n\
+            ZZZ = (ZZZ, ZZZ)";
+        let nodes = NodeArena::parse(input);
+        let ids: Vec<NodeId> = nodes.node_ids().collect();
+        assert_eq!(ids, [NodeId(0), NodeId(1), NodeId(2)]);
+        assert_eq!(nodes.id("BBB"), NodeId(1));
+        assert_eq!(nodes.name(NodeId(2)), "ZZZ");
+        assert_eq!(nodes.walk_further(NodeId(1), Direction::Right), NodeId(2));
+        assert!(nodes.is_start(NodeId(0)));
+        assert!(nodes.is_end(NodeId(2)));
+    }
+
+    #[test]
+    fn test_example() {
+        let input = "\
+            LLR\n\
+            \n\
+            AAA = (BBB, BBB)\n\
+            BBB = (AAA, ZZZ)\n\
+            ZZZ = (ZZZ, ZZZ)";
+        let result = run(input);
+        assert_eq!(result, 6);
+    }
+}
@@ -1,15 +1,156 @@
-use crate::part1::{Guide, NodeArena, NodeId};
+use crate::part1::{Direction, Guide, NodeArena, NodeId};
+
+use std::collections::{HashMap, HashSet};
 
 pub fn run(input: &str) -> usize {
-    let nodes = NodeArena::parse(input);
-    let mut guide = Guide::parse(input);
+    walk_all_ghosts(&NodeArena::parse(input), &Guide::parse(input))
+}
 
+pub fn walk_all_ghosts(nodes: &NodeArena, guide: &Guide) -> usize {
+    let cycles = ghost_cycles(nodes, guide);
+    first_common_end(&cycles).expect("the ghosts never reach the end at the same time")
+}
+
+pub fn ghost_cycles(nodes: &NodeArena, guide: &Guide) -> Vec<GhostCycle> {
     nodes
-        .inner()
-        .keys()
-        .filter(|node_id| node_id.is_start())
-        .map(|node_id| walk_until_the_end(&nodes, &mut guide, *node_id))
-        .fold(1, lcm)
+        .node_ids()
+        .filter(|node_id| nodes.is_start(*node_id))
+        .map(|node_id| GhostCycle::detect(nodes, guide.clone(), node_id))
+        .collect()
+}
+
+/// The walk of a single ghost: after `mu` steps it enters a loop of `lambda` steps, which it
+/// never leaves again. Both before and within the loop it may pass end nodes.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GhostCycle {
+    start: NodeId,
+    mu: usize,
+    lambda: usize,
+    /// steps before `mu` on which the ghost stands on an end node
+    ends_before_cycle: Vec<usize>,
+    /// steps in `mu..mu + lambda` on which the ghost stands on an end node
+    ends_in_cycle: Vec<usize>,
+}
+impl GhostCycle {
+    fn detect(nodes: &NodeArena, mut guide: Guide, start_node_id: NodeId) -> Self {
+        // the ghost only repeats itself if both node and instruction are the same again
+        let mut seen = HashMap::new();
+        let mut ends = Vec::new();
+        let mut current_node_id = start_node_id;
+        let mut num_walks = 0;
+
+        loop {
+            let state = (current_node_id, guide.position());
+            if let Some(mu) = seen.insert(state, num_walks) {
+                let lambda = num_walks - mu;
+                let (ends_before_cycle, ends_in_cycle) =
+                    ends.into_iter().partition(|step| *step < mu);
+                return Self {
+                    start: start_node_id,
+                    mu,
+                    lambda,
+                    ends_before_cycle,
+                    ends_in_cycle,
+                };
+            }
+
+            if nodes.is_end(current_node_id) {
+                ends.push(num_walks);
+            }
+            current_node_id = nodes.walk_further(current_node_id, guide.where_to());
+            num_walks += 1;
+        }
+    }
+
+    /// the edges the ghost keeps running along once it is looping
+    pub fn loop_edges(&self, nodes: &NodeArena, mut guide: Guide) -> HashSet<(NodeId, Direction)> {
+        let mut current_node_id = self.start;
+        for _ in 0..self.mu {
+            current_node_id = nodes.walk_further(current_node_id, guide.where_to());
+        }
+
+        let mut edges = HashSet::new();
+        for _ in 0..self.lambda {
+            let direction = guide.where_to();
+            edges.insert((current_node_id, direction));
+            current_node_id = nodes.walk_further(current_node_id, direction);
+        }
+        edges
+    }
+
+    fn is_end(&self, step: usize) -> bool {
+        if step < self.mu {
+            return self.ends_before_cycle.contains(&step);
+        }
+        let step_in_cycle = self.mu + (step - self.mu) % self.lambda;
+        self.ends_in_cycle.contains(&step_in_cycle)
+    }
+}
+
+fn first_common_end(cycles: &[GhostCycle]) -> Option<usize> {
+    let max_mu = cycles.iter().map(|cycle| cycle.mu).max()?;
+
+    // before everyone is looping, the ghost that needs longest to get there has only a few
+    // ends to offer
+    let slowest = cycles.iter().find(|cycle| cycle.mu == max_mu).unwrap();
+    let early_end = slowest
+        .ends_before_cycle
+        .iter()
+        .find(|step| cycles.iter().all(|cycle| cycle.is_end(**step)));
+    if let Some(step) = early_end {
+        return Some(*step);
+    }
+
+    // afterwards every ghost restricts the step to some remainders modulo its loop length
+    let mut congruences = vec![(0, 1)];
+    for cycle in cycles {
+        congruences = congruences
+            .iter()
+            .flat_map(|congruence| {
+                cycle
+                    .ends_in_cycle
+                    .iter()
+                    .filter_map(move |end| crt(*congruence, (*end % cycle.lambda, cycle.lambda)))
+            })
+            .collect();
+        congruences.sort_unstable();
+        congruences.dedup();
+    }
+
+    congruences
+        .into_iter()
+        .map(|(remainder, modulus)| {
+            if remainder >= max_mu {
+                remainder
+            } else {
+                remainder + (max_mu - remainder).div_ceil(modulus) * modulus
+            }
+        })
+        .min()
+}
+
+/// Generalized chinese remainder theorem: combine `x = a1 mod n1` and `x = a2 mod n2` to a
+/// single congruence, which only exists if both agree modulo the gcd of the moduli.
+fn crt((a1, n1): (usize, usize), (a2, n2): (usize, usize)) -> Option<(usize, usize)> {
+    let (gcd, p, _) = extended_gcd(n1 as i128, n2 as i128);
+    let difference = a2 as i128 - a1 as i128;
+    if difference % gcd != 0 {
+        return None;
+    }
+
+    let modulus = lcm(n1, n2) as i128;
+    let remainder =
+        (a1 as i128 + difference / gcd * p % (n2 as i128 / gcd) * n1 as i128).rem_euclid(modulus);
+    Some((remainder as usize, modulus as usize))
+}
+
+/// returns `(gcd, p, q)` with `p * x + q * y == gcd`
+fn extended_gcd(x: i128, y: i128) -> (i128, i128, i128) {
+    if y == 0 {
+        return (x, 1, 0);
+    }
+    let (gcd, p, q) = extended_gcd(y, x % y);
+    (gcd, q, p - x / y * q)
 }
 
 fn lcm(x: usize, y: usize) -> usize {
@@ -27,19 +168,7 @@ fn lcm(x: usize, y: usize) -> usize {
         remainder = buf1 % buf2;
     }
 
-    x * y / buf2
-}
-
-fn walk_until_the_end(nodes: &NodeArena, guide: &mut Guide, start_node_id: NodeId) -> usize {
-    let mut current_node_id = start_node_id;
-    let mut num_walks = 0;
-
-    while !current_node_id.is_end() {
-        let node = nodes.get(current_node_id);
-        current_node_id = node.walk_further(guide.where_to());
-        num_walks += 1;
-    }
-    num_walks
+    x / buf2 * y
 }
 
 #[cfg(test)]
@@ -55,6 +184,89 @@ mod tests {
         assert_eq!(lcm(6, 9), 18);
     }
 
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((0, 1), (3, 5)), Some((3, 5)));
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let input = "\
+            LR\n\
+            \n\
+            11A = (11B, XXX)\n\
+            11B = (XXX, 11Z)\n\
+            11Z = (11B, XXX)\n\
+            XXX = (XXX, XXX)";
+        let nodes = NodeArena::parse(input);
+        let guide = Guide::parse(input);
+        let start = nodes.id("11A");
+        let cycle = GhostCycle::detect(&nodes, guide, start);
+        let expected = GhostCycle {
+            start,
+            mu: 1,
+            lambda: 2,
+            ends_before_cycle: vec![],
+            ends_in_cycle: vec![2],
+        };
+        assert_eq!(cycle, expected);
+    }
+
+    #[test]
+    fn test_loop_edges() {
+        let input = "\
+            LR\n\
+            \n\
+            11A = (11B, XXX)\n\
+            11B = (XXX, 11Z)\n\
+            11Z = (11B, XXX)\n\
+            XXX = (XXX, XXX)";
+        let nodes = NodeArena::parse(input);
+        let guide = Guide::parse(input);
+        let cycle = GhostCycle::detect(&nodes, guide.clone(), nodes.id("11A"));
+        let edges = cycle.loop_edges(&nodes, guide);
+        let expected = HashSet::from([
+            (nodes.id("11B"), Direction::Right),
+            (nodes.id("11Z"), Direction::Left),
+        ]);
+        assert_eq!(edges, expected);
+    }
+
+    #[test]
+    fn test_unaligned_cycles() {
+        // ghost 1 ends after 2 steps and then every 3 steps, ghost 2 after 1 step and then every
+        // 2 steps, so a plain lcm of the first ends would claim 2
+        let input = "\
+            L\n\
+            \n\
+            11A = (11B, 11B)\n\
+            11B = (11Z, 11Z)\n\
+            11Z = (11C, 11C)\n\
+            11C = (11B, 11B)\n\
+            22A = (22Z, 22Z)\n\
+            22Z = (22B, 22B)\n\
+            22B = (22Z, 22Z)";
+        let result = run(input);
+        assert_eq!(result, 5);
+    }
+
+    #[test]
+    fn test_end_before_cycle() {
+        let input = "\
+            L\n\
+            \n\
+            11A = (11Z, 11Z)\n\
+            11Z = (11B, 11B)\n\
+            11B = (11B, 11B)\n\
+            22A = (22Z, 22Z)\n\
+            22Z = (22Z, 22Z)";
+        let result = run(input);
+        assert_eq!(result, 1);
+    }
+
     #[test]
     fn test_example() {
         let input = "\
@@ -1,5 +1,10 @@
 mod part1;
 mod part2;
+mod polynomial;
+
+use part1::CalcWhat;
+use polynomial::Polynomial;
+use timings::Stage;
 
 pub fn read_input() -> String {
     use std::fs;
@@ -8,8 +13,36 @@ pub fn read_input() -> String {
     fs::read_to_string(input_path).unwrap()
 }
 
+fn print_polynomials(input: &str, steps: usize) {
+    for line in input.lines() {
+        let history: Vec<isize> = line.split(' ').map(|s| s.parse().unwrap()).collect();
+        let polynomial = Polynomial::fit(&history);
+        let prev = polynomial.extrapolate(CalcWhat::Prev, steps);
+        let next = polynomial.extrapolate(CalcWhat::Next, steps);
+        println!(
+            "degree {}: p(x) = {polynomial}, {steps} before: {prev}, {steps} after: {next}",
+            polynomial.degree()
+        );
+    }
+}
+
 fn main() {
     let input = read_input();
+
+    // `day9 poly [steps]` shows the fitted polynomial of each history
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("poly") {
+        let steps = args.get(1).map_or(1, |steps| steps.parse().unwrap());
+        print_polynomials(&input, steps);
+        return;
+    }
+
+    // `day9 timings [warmup] [samples]` times both parts for the bench crate
+    timings::run_from_args(&mut [
+        Stage::new("part1 (incl. parse)", || part1::run(&input)),
+        Stage::new("part2 (incl. parse)", || part2::run(&input)),
+    ]);
+
     let result1 = part1::run(&input);
     let result2 = part2::run(&input);
     println!("Result1 is {result1}");
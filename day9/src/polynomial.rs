@@ -0,0 +1,279 @@
+use crate::part1::CalcWhat;
+
+use num::bigint::BigInt;
+use num::rational::BigRational;
+use num::{One, Signed, Zero};
+
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Integer that stays an `i128` as long as possible and only falls back to a big integer once
+/// an operation would overflow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Wide {
+    Narrow(i128),
+    Big(BigInt),
+}
+impl Wide {
+    fn from_big(value: BigInt) -> Self {
+        match i128::try_from(&value) {
+            Ok(narrow) => Self::Narrow(narrow),
+            Err(_) => Self::Big(value),
+        }
+    }
+    fn to_big(&self) -> BigInt {
+        match self {
+            Self::Narrow(narrow) => BigInt::from(*narrow),
+            Self::Big(big) => big.clone(),
+        }
+    }
+    fn is_zero(&self) -> bool {
+        matches!(self, Self::Narrow(0))
+    }
+    pub fn is_big(&self) -> bool {
+        matches!(self, Self::Big(_))
+    }
+    fn combine(
+        &self,
+        other: &Self,
+        narrow_op: fn(i128, i128) -> Option<i128>,
+        big_op: fn(BigInt, BigInt) -> BigInt,
+    ) -> Self {
+        if let (Self::Narrow(x), Self::Narrow(y)) = (self, other) {
+            if let Some(result) = narrow_op(*x, *y) {
+                return Self::Narrow(result);
+            }
+        }
+        Self::from_big(big_op(self.to_big(), other.to_big()))
+    }
+}
+impl From<isize> for Wide {
+    fn from(value: isize) -> Self {
+        Self::Narrow(value as i128)
+    }
+}
+impl std::fmt::Display for Wide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Narrow(narrow) => write!(f, "{narrow}"),
+            Self::Big(big) => write!(f, "{big}"),
+        }
+    }
+}
+impl Add for &Wide {
+    type Output = Wide;
+    fn add(self, other: Self) -> Wide {
+        self.combine(other, i128::checked_add, |x, y| x + y)
+    }
+}
+impl Sub for &Wide {
+    type Output = Wide;
+    fn sub(self, other: Self) -> Wide {
+        self.combine(other, i128::checked_sub, |x, y| x - y)
+    }
+}
+impl Mul for &Wide {
+    type Output = Wide;
+    fn mul(self, other: Self) -> Wide {
+        self.combine(other, i128::checked_mul, |x, y| x * y)
+    }
+}
+impl Div for &Wide {
+    type Output = Wide;
+    fn div(self, other: Self) -> Wide {
+        self.combine(other, i128::checked_div, |x, y| x / y)
+    }
+}
+
+/// The exact interpolating polynomial of a history, where `x == 0` is its first element.
+///
+/// It is stored in Newton form `p(x) = d_0 + d_1 * (x choose 1) + d_2 * (x choose 2) + ...`,
+/// with `d_k` being the first entry of the `k`-th line of differences (see [`crate::part1::calc`]).
+/// As `x choose k` is an integer for every integer `x`, all extrapolated values stay integers.
+pub struct Polynomial {
+    differences: Vec<Wide>,
+    history_len: usize,
+}
+impl Polynomial {
+    pub fn fit(history: &[isize]) -> Self {
+        assert!(!history.is_empty());
+
+        let mut line: Vec<Wide> = history.iter().map(|value| Wide::from(*value)).collect();
+        let mut differences = Vec::with_capacity(history.len());
+        while let Some(first) = line.first() {
+            differences.push(first.clone());
+            line = line.windows(2).map(|pair| &pair[1] - &pair[0]).collect();
+        }
+
+        // once a line is all zeros, every following line is too
+        while differences.len() > 1 && differences.last().unwrap().is_zero() {
+            differences.pop();
+        }
+
+        Self {
+            differences,
+            history_len: history.len(),
+        }
+    }
+
+    /// the zero polynomial has degree 0 as well
+    pub fn degree(&self) -> usize {
+        self.differences.len() - 1
+    }
+
+    pub fn value_at(&self, x: isize) -> Wide {
+        let x = Wide::from(x);
+        let mut binomial = Wide::Narrow(1);
+        let mut value = Wide::Narrow(0);
+        for (k, difference) in self.differences.iter().enumerate() {
+            value = &value + &(difference * &binomial);
+
+            // (x choose k+1) == (x choose k) * (x - k) / (k + 1), which always divides exactly
+            let k = k as isize;
+            binomial = &(&binomial * &(&x - &Wide::from(k))) / &Wide::from(k + 1);
+        }
+        value
+    }
+
+    /// `steps` elements after the last or before the first element of the history
+    pub fn extrapolate(&self, calc_what: CalcWhat, steps: usize) -> Wide {
+        let x = match calc_what {
+            CalcWhat::Next => (self.history_len - 1 + steps) as isize,
+            CalcWhat::Prev => -(steps as isize),
+        };
+        self.value_at(x)
+    }
+
+    /// Coefficients in monomial form, starting with the constant one.
+    pub fn coefficients(&self) -> Vec<BigRational> {
+        let mut coefficients = vec![BigRational::zero(); self.differences.len()];
+
+        // x * (x - 1) * ... * (x - k + 1), also starting with the constant coefficient
+        let mut falling_factorial = vec![BigInt::one()];
+        let mut factorial = BigInt::one();
+        for (k, difference) in self.differences.iter().enumerate() {
+            let scale = BigRational::new(difference.to_big(), factorial.clone());
+            for (coefficient, factor) in coefficients.iter_mut().zip(&falling_factorial) {
+                *coefficient += &scale * BigRational::from_integer(factor.clone());
+            }
+
+            let k = BigInt::from(k);
+            let mut next = vec![BigInt::zero(); falling_factorial.len() + 1];
+            for (power, factor) in falling_factorial.iter().enumerate() {
+                next[power + 1] += factor;
+                next[power] -= &k * factor;
+            }
+            falling_factorial = next;
+            factorial *= k + 1;
+        }
+        coefficients
+    }
+}
+impl std::fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms: Vec<String> = self
+            .coefficients()
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, coefficient)| !coefficient.is_zero())
+            .map(|(power, coefficient)| {
+                let coefficient = if coefficient.is_negative() {
+                    format!("({coefficient})")
+                } else {
+                    format!("{coefficient}")
+                };
+                match power {
+                    0 => coefficient,
+                    1 => format!("{coefficient}*x"),
+                    _ => format!("{coefficient}*x^{power}"),
+                }
+            })
+            .collect();
+
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(numerator: isize, denominator: isize) -> BigRational {
+        BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    #[test]
+    fn test_degree() {
+        assert_eq!(Polynomial::fit(&[0, 0, 0]).degree(), 0);
+        assert_eq!(Polynomial::fit(&[3, 3, 3]).degree(), 0);
+        assert_eq!(Polynomial::fit(&[0, 3, 6, 9, 12, 15]).degree(), 1);
+        assert_eq!(Polynomial::fit(&[1, 3, 6, 10, 15, 21]).degree(), 2);
+        assert_eq!(Polynomial::fit(&[10, 13, 16, 21, 30, 45]).degree(), 3);
+    }
+
+    #[test]
+    fn test_coefficients() {
+        // triangular numbers shifted by one: (x + 1) * (x + 2) / 2
+        let polynomial = Polynomial::fit(&[1, 3, 6, 10, 15, 21]);
+        assert_eq!(
+            polynomial.coefficients(),
+            [ratio(1, 1), ratio(3, 2), ratio(1, 2)]
+        );
+        assert_eq!(polynomial.to_string(), "1/2*x^2 + 3/2*x + 1");
+
+        let polynomial = Polynomial::fit(&[0, -1, -3, -6]);
+        assert_eq!(polynomial.to_string(), "(-1/2)*x^2 + (-1/2)*x");
+    }
+
+    #[test]
+    fn test_extrapolate_matches_calc() {
+        let histories: [&[isize]; 4] = [
+            &[0, 3, 6, 9, 12, 15],
+            &[1, 3, 6, 10, 15, 21],
+            &[10, 13, 16, 21, 30, 45],
+            &[4, 2, 1, 1],
+        ];
+        for history in histories {
+            let polynomial = Polynomial::fit(history);
+            for calc_what in [CalcWhat::Next, CalcWhat::Prev] {
+                let expected = crate::part1::calc(history.iter().copied(), calc_what);
+                assert_eq!(polynomial.extrapolate(calc_what, 1), Wide::from(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_extrapolate_multiple_steps() {
+        let polynomial = Polynomial::fit(&[10, 13, 16, 21, 30, 45]);
+        assert_eq!(polynomial.extrapolate(CalcWhat::Next, 2), Wide::Narrow(101));
+        assert_eq!(polynomial.extrapolate(CalcWhat::Prev, 2), Wide::Narrow(-4));
+        assert_eq!(polynomial.extrapolate(CalcWhat::Next, 0), Wide::Narrow(45));
+        assert_eq!(polynomial.extrapolate(CalcWhat::Prev, 0), Wide::Narrow(10));
+    }
+
+    #[test]
+    fn test_wide_fallback() {
+        // alternating history: the k-th difference is (-2)^k, which overflows i128
+        let history: Vec<isize> = (0..130).map(|i| if i % 2 == 0 { 1 } else { -1 }).collect();
+        let polynomial = Polynomial::fit(&history);
+        assert_eq!(polynomial.degree(), 129);
+
+        let two_pow = BigInt::from(2).pow(130);
+        let next = polynomial.extrapolate(CalcWhat::Next, 1);
+        assert!(next.is_big());
+        assert_eq!(next, Wide::Big(BigInt::one() - &two_pow));
+        let prev = polynomial.extrapolate(CalcWhat::Prev, 1);
+        assert_eq!(prev, Wide::Big(two_pow - BigInt::one()));
+    }
+
+    #[test]
+    fn test_wide_demotes() {
+        let big = &Wide::Narrow(i128::MAX) + &Wide::Narrow(1);
+        assert!(big.is_big());
+        let narrow = &big - &Wide::Narrow(2);
+        assert_eq!(narrow, Wide::Narrow(i128::MAX - 1));
+    }
+}
@@ -0,0 +1,150 @@
+use std::time::{Duration, Instant};
+
+/// One timed stage of a day, e.g. parsing or one of the parts.
+pub struct Stage<'a> {
+    name: &'a str,
+    sample: Box<dyn FnMut(usize, usize) -> Vec<Duration> + 'a>,
+}
+
+impl<'a> Stage<'a> {
+    /// Times every call of `f`.
+    pub fn new<T>(name: &'a str, mut f: impl FnMut() -> T + 'a) -> Self {
+        let sample = Box::new(move |warmup, num_samples| sample(warmup, num_samples, &mut f));
+        Self { name, sample }
+    }
+
+    /// Times only `f`, which gets a fresh value from the untimed `setup` on every call. This is
+    /// how a part is timed on the already parsed input.
+    pub fn with_setup<S, T>(
+        name: &'a str,
+        mut setup: impl FnMut() -> S + 'a,
+        mut f: impl FnMut(S) -> T + 'a,
+    ) -> Self {
+        let sample = Box::new(move |warmup, num_samples| {
+            sample_with_setup(warmup, num_samples, &mut setup, &mut f)
+        });
+        Self { name, sample }
+    }
+}
+
+/// Calls `f` `warmup` times without measuring, and then measures `num_samples` more calls.
+fn sample<T>(warmup: usize, num_samples: usize, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    sample_with_setup(warmup, num_samples, || (), |()| f())
+}
+
+fn sample_with_setup<S, T>(
+    warmup: usize,
+    num_samples: usize,
+    mut setup: impl FnMut() -> S,
+    mut f: impl FnMut(S) -> T,
+) -> Vec<Duration> {
+    for _ in 0..warmup {
+        std::hint::black_box(f(setup()));
+    }
+    (0..num_samples)
+        .map(|_| {
+            let value = setup();
+            let start = Instant::now();
+            std::hint::black_box(f(value));
+            start.elapsed()
+        })
+        .collect()
+}
+
+/// The samples of each stage in nanoseconds, as the single line of JSON the bench crate reads.
+fn report(stages: &[(&str, Vec<Duration>)]) -> String {
+    let stages: Vec<String> = stages
+        .iter()
+        .map(|(stage, samples)| {
+            let nanos: Vec<String> = samples
+                .iter()
+                .map(|sample| sample.as_nanos().to_string())
+                .collect();
+            format!("\"{stage}\":[{}]", nanos.join(","))
+        })
+        .collect();
+    format!("{{{}}}", stages.join(","))
+}
+
+/// Handles `dayN timings [warmup] [samples]` for the bench crate: prints the report of all
+/// stages and exits. Does nothing for any other command line.
+pub fn run_from_args(stages: &mut [Stage]) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((warmup, num_samples)) = parse_args(&args) {
+        println!("{}", run(stages, warmup, num_samples));
+        std::process::exit(0);
+    }
+}
+
+fn parse_args(args: &[String]) -> Option<(usize, usize)> {
+    if args.first().map(String::as_str) != Some("timings") {
+        return None;
+    }
+    let warmup = args.get(1).map_or(3, |warmup| warmup.parse().unwrap());
+    let num_samples = args.get(2).map_or(10, |samples| samples.parse().unwrap());
+    Some((warmup, num_samples))
+}
+
+fn run(stages: &mut [Stage], warmup: usize, num_samples: usize) -> String {
+    let samples: Vec<_> = stages
+        .iter_mut()
+        .map(|stage| (stage.name, (stage.sample)(warmup, num_samples)))
+        .collect();
+    report(&samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        let mut num_calls = 0;
+        let samples = sample(2, 3, || num_calls += 1);
+        assert_eq!(samples.len(), 3);
+        assert_eq!(num_calls, 5);
+    }
+
+    #[test]
+    fn test_report() {
+        let stages = [
+            ("parse", vec![Duration::from_nanos(5)]),
+            (
+                "part1",
+                vec![Duration::from_micros(1), Duration::from_nanos(7)],
+            ),
+        ];
+        assert_eq!(report(&stages), r#"{"parse":[5],"part1":[1000,7]}"#);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(parse_args(&args(&[])), None);
+        assert_eq!(parse_args(&args(&["layers", "input"])), None);
+        assert_eq!(parse_args(&args(&["timings"])), Some((3, 10)));
+        assert_eq!(parse_args(&args(&["timings", "1", "5"])), Some((1, 5)));
+    }
+
+    #[test]
+    fn test_run_stages() {
+        let mut num_setups = 0;
+        let mut num_calls = 0;
+        let mut stages = [
+            Stage::new("parse", || ()),
+            Stage::with_setup(
+                "part1",
+                || num_setups += 1,
+                |()| {
+                    num_calls += 1;
+                },
+            ),
+        ];
+        let report = run(&mut stages, 1, 2);
+        drop(stages);
+        assert!(report.starts_with(r#"{"parse":["#));
+        assert!(report.contains(r#"],"part1":["#));
+        assert_eq!(num_setups, 3);
+        assert_eq!(num_calls, 3);
+    }
+}